@@ -31,6 +31,7 @@ describe("emotitle info command", () => {
     await debugPrint("=== pipe output ===");
     await debugPrint(info);
 
+    expect(info.permission).toBe("granted");
     expect(info.tabs).toBeInstanceOf(Array);
     expect(info.tabs.length).toBeGreaterThan(0);
     expect(info.tabs[0]).toHaveProperty("position");
@@ -59,6 +60,30 @@ describe("emotitle info command", () => {
     expect(output).toBe("ok");
   }, 30000);
 
+  test("should report invalid plugin config in config_errors", async () => {
+    using zellijSession = await launchZellijSession({
+      pluginConfig: {
+        restore_delay: "soon",
+        no_such_key: "1",
+        separator: "{title}",
+      },
+    });
+    const { session, configDir, cacheDir, sessionName } = zellijSession;
+
+    await session.press("esc");
+    await sleep(200);
+
+    const info = await getInfo(configDir, cacheDir, sessionName);
+
+    expect(info.config_errors).toContain("unknown config key: no_such_key");
+    expect(
+      info.config_errors.some((e: string) => e.startsWith("restore_delay")),
+    ).toBe(true);
+    expect(
+      info.config_errors.some((e: string) => e.startsWith("separator")),
+    ).toBe(true);
+  }, 30000);
+
   describe("event_history", () => {
     test("should record TabAdded event when tab is created", async () => {
       using zellijSession = await launchZellijSession();
@@ -135,6 +160,30 @@ describe("emotitle info command", () => {
       expect(updatedEvents.length).toBeGreaterThanOrEqual(1);
     }, 30000);
 
+    test("should record TabMoved event when tab is moved", async () => {
+      using zellijSession = await launchZellijSession();
+      const { session, configDir, cacheDir, sessionName } = zellijSession;
+
+      await session.press("esc");
+      await sleep(200);
+
+      await zellijAction(configDir, cacheDir, sessionName, "new-tab");
+      await sleep(300);
+      await zellijAction(configDir, cacheDir, sessionName, "move-tab", [
+        "left",
+      ]);
+      await sleep(300);
+
+      const info = await getInfo(configDir, cacheDir, sessionName);
+
+      const movedEvents = info.event_history.filter(
+        (e: { event_type: string }) => e.event_type === "TabMoved",
+      );
+      expect(movedEvents.length).toBeGreaterThanOrEqual(1);
+      expect(movedEvents[0]).toHaveProperty("tab_id");
+      expect(movedEvents[0]).toHaveProperty("position");
+    }, 30000);
+
     test("should maintain sequential event order", async () => {
       using zellijSession = await launchZellijSession();
       const { session, configDir, cacheDir, sessionName } = zellijSession;
@@ -156,6 +205,64 @@ describe("emotitle info command", () => {
       }
     }, 30000);
 
+    test("should verify the internal index once a tab rename lands", async () => {
+      using zellijSession = await launchZellijSession();
+      const { session, configDir, cacheDir, sessionName } = zellijSession;
+
+      await session.press("esc");
+      await sleep(200);
+
+      let info = await getInfo(configDir, cacheDir, sessionName);
+      expect(
+        info.internal_index_map.every(
+          (e: { verified: boolean }) => !e.verified,
+        ),
+      ).toBe(true);
+
+      await runPipe(
+        session,
+        configDir,
+        cacheDir,
+        sessionName,
+        "target=tab,emojis=📌✅",
+      );
+      await sleep(300);
+
+      info = await getInfo(configDir, cacheDir, sessionName);
+      expect(
+        info.internal_index_map.some((e: { verified: boolean }) => e.verified),
+      ).toBe(true);
+    }, 30000);
+
+    test("should not record TabRenameAnomaly when renames land on the expected tab", async () => {
+      using zellijSession = await launchZellijSession();
+      const { session, configDir, cacheDir, sessionName } = zellijSession;
+
+      await session.press("esc");
+      await sleep(200);
+
+      await zellijAction(configDir, cacheDir, sessionName, "new-tab");
+      await sleep(300);
+
+      await runPipe(
+        session,
+        configDir,
+        cacheDir,
+        sessionName,
+        "target=tab,tab_index=0..1,emojis=📌✅",
+      );
+      await sleep(1500);
+
+      const info = await getInfo(configDir, cacheDir, sessionName);
+      const anomalies = info.event_history.filter(
+        (e: { event_type: string }) => e.event_type === "TabRenameAnomaly",
+      );
+      expect(anomalies.length).toBe(0);
+      expect(
+        info.internal_index_map.every((e: { verified: boolean }) => e.verified),
+      ).toBe(true);
+    }, 30000);
+
     test("should limit event history to 200 entries", async () => {
       using zellijSession = await launchZellijSession();
       const { session, configDir, cacheDir, sessionName } = zellijSession;
@@ -1,6 +1,7 @@
 import { describe, expect, test } from "bun:test";
 import type { Session } from "tuistory";
 import {
+  getInfo,
   launchZellijSession,
   runPipe,
   sleep,
@@ -74,6 +75,129 @@ describe("emotitle plugin (pane target)", () => {
     });
   });
 
+  describe("when the plugin is started by the first pipe", () => {
+    test("should queue the command until the plugin knows the panes", async () => {
+      using zellijSession = await launchZellijSession({ loadPlugin: false });
+      const { session, configDir, cacheDir, sessionName } = zellijSession;
+
+      await session.press("esc");
+      await sleep(200);
+
+      const output = await runPipe(
+        session,
+        configDir,
+        cacheDir,
+        sessionName,
+        "target=pane,emojis=📌🚀",
+      );
+      expect(output).toBe("ok");
+      await sleep(300);
+
+      const text = await session.text();
+      expect(text).toContain("📌🚀");
+    }, 30000);
+  });
+
+  describe("when follow=true is given", () => {
+    test("should keep pinned emojis over a title set by the program", async () => {
+      const context = await setupSession();
+      const { session, configDir, cacheDir, sessionName } = context;
+
+      await runPipe(
+        session,
+        configDir,
+        cacheDir,
+        sessionName,
+        "target=pane,emojis=📌🚧,follow=true",
+      );
+      await sleep(300);
+
+      await session.type("printf '\\033]0;FOLLOWED_TITLE\\007'; sleep 10");
+      await session.press("enter");
+      await sleep(500);
+
+      // Moving the focus off the pane is what uncovers the new title.
+      await zellijAction(configDir, cacheDir, sessionName, "new-pane");
+      await sleep(1000);
+
+      const text = await session.text();
+      expect(text).toContain("FOLLOWED_TITLE | 📌🚧");
+    }, 30000);
+  });
+
+  describe("when pane_template separates the title with a plain space", () => {
+    test("should keep the spaces of the title on decorate and restore", async () => {
+      using zellijSession = await launchZellijSession({
+        pluginConfig: { pane_template: "{title} {emojis}" },
+      });
+      const { session, configDir, cacheDir, sessionName } = zellijSession;
+      await session.press("esc");
+      await sleep(200);
+
+      await zellijAction(configDir, cacheDir, sessionName, "rename-pane", [
+        "MY PANE #1",
+      ]);
+      await sleep(300);
+
+      await runPipe(
+        session,
+        configDir,
+        cacheDir,
+        sessionName,
+        "target=pane,emojis=📌✅",
+      );
+      await runPipe(
+        session,
+        configDir,
+        cacheDir,
+        sessionName,
+        "target=pane,emojis=🚀",
+      );
+      await sleep(300);
+
+      let text = await session.text();
+      expect(text).toContain("MY PANE #1 📌✅ | 🚀");
+
+      await sleep(1300);
+
+      text = await session.text();
+      expect(text).toContain("MY PANE #1 📌✅");
+      expect(text).not.toContain("🚀");
+    }, 60000);
+  });
+
+  describe("when a floating pane is shown", () => {
+    type PaneInfo = { title: string; is_floating: boolean; is_plugin: boolean };
+
+    test("should decorate the focused tiled pane with floating=false", async () => {
+      const context = await setupSession();
+      const { session, configDir, cacheDir, sessionName } = context;
+
+      await zellijAction(configDir, cacheDir, sessionName, "new-pane", [
+        "--floating",
+      ]);
+      await sleep(500);
+
+      await runPipe(
+        session,
+        configDir,
+        cacheDir,
+        sessionName,
+        "target=pane,floating=false,emojis=📌🧱",
+      );
+      await sleep(300);
+
+      const info = await getInfo(configDir, cacheDir, sessionName);
+      const panes: PaneInfo[] = info.tabs[0].panes.filter(
+        (pane: PaneInfo) => !pane.is_plugin,
+      );
+      const tiled = panes.find((pane) => !pane.is_floating);
+      const floating = panes.find((pane) => pane.is_floating);
+      expect(tiled?.title).toContain("📌🧱");
+      expect(floating?.title).not.toContain("📌🧱");
+    }, 30000);
+  });
+
   describe("when the target is not specified", () => {
     const pinEmojiToCurrent = async (options: PinOptions) => {
       await pinEmojiToPane({
@@ -227,6 +351,28 @@ describe("emotitle plugin (pane target)", () => {
       }, 60000);
     });
 
+    describe("when commands are sent back to back", () => {
+      test("should keep every pinned emoji and drop only the temporary one", async () => {
+        const context = await setupSession();
+        const { session, configDir, cacheDir, sessionName } = context;
+
+        for (const emojis of ["📌🚀", "📚", "📌🎉"]) {
+          await runPipe(
+            session,
+            configDir,
+            cacheDir,
+            sessionName,
+            `target=pane,emojis=${emojis}`,
+          );
+        }
+        await sleep(1500);
+
+        const text = await session.text();
+        expect(text).toContain("📌🚀 | 📌🎉");
+        expect(text).not.toContain("📚");
+      }, 30000);
+    });
+
     describe("when the emoji type is non-pinned", () => {
       const pinNonPinnedEmoji = async (options: PinOptions) => {
         await pinEmojiToCurrent({
@@ -259,6 +405,128 @@ describe("emotitle plugin (pane target)", () => {
         expect(text).not.toContain("📚");
       }, 60000);
 
+      test("should let the program retitle a pane left before the restore", async () => {
+        using context = await setupSession();
+        const { session, configDir, cacheDir, sessionName } = context;
+
+        await pinNonPinnedEmoji({ context });
+        await zellijAction(configDir, cacheDir, sessionName, "new-pane");
+        await sleep(1500);
+
+        await zellijAction(
+          configDir,
+          cacheDir,
+          sessionName,
+          "focus-previous-pane",
+        );
+        await sleep(300);
+        await session.type("printf '\\033]0;PROGRAM_TITLE\\007'; sleep 10");
+        await session.press("enter");
+        await sleep(500);
+
+        const text = await session.text();
+        expect(text).not.toContain("📚");
+        expect(text).toContain("PROGRAM_TITLE");
+      }, 60000);
+
+      test("should collapse repeated emojis into a counter", async () => {
+        const context = await setupSession();
+        const { session, configDir, cacheDir, sessionName } = context;
+
+        await zellijAction(configDir, cacheDir, sessionName, "new-pane");
+        await sleep(500);
+
+        for (let i = 0; i < 3; i++) {
+          await runPipe(
+            session,
+            configDir,
+            cacheDir,
+            sessionName,
+            "target=pane,pane_id=0,emojis=🔔",
+          );
+          await sleep(200);
+        }
+
+        const text = await session.text();
+        expect(text).toContain("🔔×3");
+        expect(text).not.toContain("🔔 | 🔔");
+      }, 60000);
+
+      test("should evict the oldest temporary emoji beyond max_segments", async () => {
+        using zellijSession = await launchZellijSession({
+          pluginConfig: { max_segments: "2" },
+        });
+        const { session, configDir, cacheDir, sessionName } = zellijSession;
+        await session.press("esc");
+        await sleep(200);
+
+        await zellijAction(configDir, cacheDir, sessionName, "new-pane");
+        await sleep(500);
+
+        for (const emojis of ["🍎", "📌🍌"]) {
+          await runPipe(
+            session,
+            configDir,
+            cacheDir,
+            sessionName,
+            `target=pane,pane_id=0,emojis=${emojis}`,
+          );
+        }
+        const output = await runPipe(
+          session,
+          configDir,
+          cacheDir,
+          sessionName,
+          "target=pane,pane_id=0,emojis=🍒",
+        );
+        await sleep(300);
+
+        expect(output).toBe("ok (evicted: 🍎)");
+        const text = await session.text();
+        expect(text).toContain("📌🍌 | 🍒");
+        expect(text).not.toContain("🍎");
+      }, 60000);
+
+      test("should cycle animate= frames until the pane is focused", async () => {
+        const context = await setupSession();
+        const { session, configDir, cacheDir, sessionName } = context;
+
+        await zellijAction(configDir, cacheDir, sessionName, "new-pane");
+        await sleep(500);
+
+        await runPipe(
+          session,
+          configDir,
+          cacheDir,
+          sessionName,
+          "target=pane,pane_id=0,animate=🌑🌕,interval=300",
+        );
+
+        const seen = new Set<string>();
+        for (let i = 0; i < 4; i++) {
+          await sleep(300);
+          const text = await session.text();
+          for (const frame of ["🌑", "🌕"]) {
+            if (text.includes(frame)) {
+              seen.add(frame);
+            }
+          }
+        }
+        expect(seen.size).toBe(2);
+
+        await zellijAction(
+          configDir,
+          cacheDir,
+          sessionName,
+          "focus-previous-pane",
+        );
+        await sleep(1500);
+
+        const text = await session.text();
+        expect(text).not.toContain("🌑");
+        expect(text).not.toContain("🌕");
+      }, 60000);
+
       test("should not resurrect deleted emojis on setting new emojis", async () => {
         const context = await setupSession();
         await pinNonPinnedEmoji({ context });
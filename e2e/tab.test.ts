@@ -1,4 +1,6 @@
 import { describe, expect, test } from "bun:test";
+import { writeFileSync } from "node:fs";
+import { join } from "node:path";
 import type { Session } from "tuistory";
 import {
   getInfo,
@@ -270,6 +272,36 @@ describe("emotitle plugin (tab target)", () => {
         expect(text).not.toContain("📚");
       }, 60000);
 
+      test("should give the default tab name back to zellij on restore", async () => {
+        const context = await setupSession();
+        const { configDir, cacheDir, sessionName } = context;
+
+        await zellijAction(configDir, cacheDir, sessionName, "new-tab");
+        await sleep(300);
+        await pinNonPinnedEmojiToTabByTabIndex({ context, tab_index: 1 });
+        await sleep(1300);
+
+        let tabNames = (
+          await queryTabNames(configDir, cacheDir, sessionName)
+        )
+          .trim()
+          .split("\n");
+        expect(tabNames).toEqual(["Tab #1", "Tab #2"]);
+
+        // Only a name zellij still owns is renumbered; a title frozen by a
+        // rename would stay "Tab #2".
+        await zellijAction(configDir, cacheDir, sessionName, "go-to-tab", [
+          "1",
+        ]);
+        await zellijAction(configDir, cacheDir, sessionName, "close-tab");
+        await sleep(500);
+
+        tabNames = (await queryTabNames(configDir, cacheDir, sessionName))
+          .trim()
+          .split("\n");
+        expect(tabNames).toEqual(["Tab #1"]);
+      }, 60000);
+
       test("should remove emojis after tab switch", async () => {
         const context = await setupSession();
         const { session, configDir, cacheDir, sessionName } = context;
@@ -298,6 +330,45 @@ describe("emotitle plugin (tab target)", () => {
         expect(text).not.toContain("📚");
       }, 60000);
 
+      describe("when multiple tab indices are given", () => {
+        test("should apply emojis to every tab in the range", async () => {
+          const context = await setupSession();
+          const { session, configDir, cacheDir, sessionName } = context;
+
+          await zellijAction(configDir, cacheDir, sessionName, "rename-tab", [
+            "TAB_A",
+          ]);
+          await zellijAction(configDir, cacheDir, sessionName, "new-tab");
+          await zellijAction(configDir, cacheDir, sessionName, "rename-tab", [
+            "TAB_B",
+          ]);
+          await zellijAction(configDir, cacheDir, sessionName, "new-tab");
+          await zellijAction(configDir, cacheDir, sessionName, "rename-tab", [
+            "TAB_C",
+          ]);
+          await sleep(300);
+
+          const output = await runPipe(
+            session,
+            configDir,
+            cacheDir,
+            sessionName,
+            "target=tab,tab_index=0..1,emojis=📚",
+          );
+          await sleep(300);
+
+          expect(output).toContain("tab_index=0: ok");
+          expect(output).toContain("tab_index=1: ok");
+
+          const tabNames = (
+            await queryTabNames(configDir, cacheDir, sessionName)
+          ).split("\n");
+          expect(tabNames).toContain("TAB_A | 📚");
+          expect(tabNames).toContain("TAB_B | 📚");
+          expect(tabNames).toContain("TAB_C");
+        }, 60000);
+      });
+
       describe("when some tab are deleted", () => {
         const createSomeTabsWithDeleted = async ({
           configDir,
@@ -452,6 +523,721 @@ describe("emotitle plugin (tab target)", () => {
     });
   });
 
+  describe("when the target is broadcast", () => {
+    test("should apply emojis to every tab except the focused one", async () => {
+      const context = await setupSession();
+      const { session, configDir, cacheDir, sessionName } = context;
+
+      await zellijAction(configDir, cacheDir, sessionName, "rename-tab", [
+        "TAB_A",
+      ]);
+      await zellijAction(configDir, cacheDir, sessionName, "new-tab");
+      await zellijAction(configDir, cacheDir, sessionName, "rename-tab", [
+        "TAB_B",
+      ]);
+      await zellijAction(configDir, cacheDir, sessionName, "new-tab");
+      await zellijAction(configDir, cacheDir, sessionName, "rename-tab", [
+        "TAB_C",
+      ]);
+      await sleep(300);
+
+      await runPipe(
+        session,
+        configDir,
+        cacheDir,
+        sessionName,
+        "target=unfocused,emojis=🚨",
+      );
+      await sleep(300);
+
+      let tabNames = (
+        await queryTabNames(configDir, cacheDir, sessionName)
+      ).split("\n");
+      expect(tabNames).toContain("TAB_A | 🚨");
+      expect(tabNames).toContain("TAB_B | 🚨");
+      expect(tabNames).toContain("TAB_C");
+
+      await zellijAction(configDir, cacheDir, sessionName, "go-to-tab-name", [
+        "TAB_B",
+      ]);
+      await sleep(1300);
+
+      tabNames = (await queryTabNames(configDir, cacheDir, sessionName)).split(
+        "\n",
+      );
+      expect(tabNames).toContain("TAB_A | 🚨");
+      expect(tabNames).toContain("TAB_B");
+    }, 60000);
+  });
+
+  describe("when restore_delay is configured", () => {
+    test("should count the delay from each tab's own focus", async () => {
+      using zellijSession = await launchZellijSession({
+        pluginConfig: { restore_delay: "3" },
+      });
+      const { session, configDir, cacheDir, sessionName } = zellijSession;
+      await session.press("esc");
+      await sleep(200);
+
+      await zellijAction(configDir, cacheDir, sessionName, "rename-tab", [
+        "TAB_A",
+      ]);
+      await zellijAction(configDir, cacheDir, sessionName, "new-tab");
+      await sleep(300);
+      await zellijAction(configDir, cacheDir, sessionName, "rename-tab", [
+        "TAB_B",
+      ]);
+      await sleep(200);
+
+      await runPipe(
+        session,
+        configDir,
+        cacheDir,
+        sessionName,
+        "target=tab,tab_index=0,emojis=📚",
+      );
+      await runPipe(
+        session,
+        configDir,
+        cacheDir,
+        sessionName,
+        "target=tab,tab_index=1,emojis=🔔",
+      );
+      await sleep(2000);
+
+      await zellijAction(configDir, cacheDir, sessionName, "go-to-tab", ["1"]);
+      await sleep(1500);
+
+      let tabNames = (
+        await queryTabNames(configDir, cacheDir, sessionName)
+      ).split("\n");
+      expect(tabNames).toContain("TAB_B");
+      expect(tabNames).toContain("TAB_A | 📚");
+
+      await sleep(2500);
+
+      tabNames = (await queryTabNames(configDir, cacheDir, sessionName)).split(
+        "\n",
+      );
+      expect(tabNames).toContain("TAB_A");
+    }, 60000);
+  });
+
+  describe("when min_dwell is configured", () => {
+    test("should keep emojis on a tab the user only flicks through", async () => {
+      using zellijSession = await launchZellijSession({
+        pluginConfig: { min_dwell: "2" },
+      });
+      const { session, configDir, cacheDir, sessionName } = zellijSession;
+      await session.press("esc");
+      await sleep(200);
+
+      await zellijAction(configDir, cacheDir, sessionName, "rename-tab", [
+        "TAB_A",
+      ]);
+      await zellijAction(configDir, cacheDir, sessionName, "new-tab");
+      await sleep(300);
+
+      await runPipe(
+        session,
+        configDir,
+        cacheDir,
+        sessionName,
+        "target=tab,tab_index=0,emojis=📚",
+      );
+      await sleep(200);
+
+      await zellijAction(configDir, cacheDir, sessionName, "go-to-tab", ["1"]);
+      await sleep(300);
+      await zellijAction(configDir, cacheDir, sessionName, "go-to-tab", ["2"]);
+      await sleep(1500);
+
+      let tabNames = (
+        await queryTabNames(configDir, cacheDir, sessionName)
+      ).split("\n");
+      expect(tabNames).toContain("TAB_A | 📚");
+
+      await zellijAction(configDir, cacheDir, sessionName, "go-to-tab", ["1"]);
+      await sleep(2800);
+
+      tabNames = (await queryTabNames(configDir, cacheDir, sessionName)).split(
+        "\n",
+      );
+      expect(tabNames).toContain("TAB_A");
+    }, 60000);
+  });
+
+  describe("when tab_template is configured", () => {
+    test("should place emojis before the title and keep pinned segments", async () => {
+      using zellijSession = await launchZellijSession({
+        pluginConfig: { tab_template: "{emojis} {title}" },
+      });
+      const { session, configDir, cacheDir, sessionName } = zellijSession;
+      await session.press("esc");
+      await sleep(200);
+
+      await zellijAction(configDir, cacheDir, sessionName, "rename-tab", [
+        "MY TAB",
+      ]);
+      await zellijAction(configDir, cacheDir, sessionName, "new-tab");
+      await sleep(300);
+
+      await runPipe(
+        session,
+        configDir,
+        cacheDir,
+        sessionName,
+        "target=tab,tab_index=0,emojis=📌✅",
+      );
+      await runPipe(
+        session,
+        configDir,
+        cacheDir,
+        sessionName,
+        "target=tab,tab_index=0,emojis=🚀",
+      );
+      await sleep(300);
+
+      let tabNames = (
+        await queryTabNames(configDir, cacheDir, sessionName)
+      ).split("\n");
+      expect(tabNames).toContain("📌✅ | 🚀 MY TAB");
+
+      await zellijAction(configDir, cacheDir, sessionName, "go-to-tab", ["1"]);
+      await sleep(1500);
+
+      tabNames = (await queryTabNames(configDir, cacheDir, sessionName)).split(
+        "\n",
+      );
+      expect(tabNames).toContain("📌✅ MY TAB");
+    }, 60000);
+
+    test("should keep a title that starts with an emoji", async () => {
+      using zellijSession = await launchZellijSession({
+        pluginConfig: { tab_template: "{emojis} {title}" },
+      });
+      const { session, configDir, cacheDir, sessionName } = zellijSession;
+      await session.press("esc");
+      await sleep(200);
+
+      await zellijAction(configDir, cacheDir, sessionName, "rename-tab", [
+        "🏠 home",
+      ]);
+      await sleep(300);
+
+      await runPipe(
+        session,
+        configDir,
+        cacheDir,
+        sessionName,
+        "target=tab,tab_index=0,emojis=📌✅",
+      );
+      await runPipe(
+        session,
+        configDir,
+        cacheDir,
+        sessionName,
+        "target=tab,tab_index=0,emojis=📌🚀",
+      );
+      await sleep(300);
+
+      const tabNames = (
+        await queryTabNames(configDir, cacheDir, sessionName)
+      ).split("\n");
+      expect(tabNames).toContain("📌✅ | 📌🚀 🏠 home");
+    }, 60000);
+  });
+
+  describe("when statuses and aliases are configured", () => {
+    test("should map status= and :alias: to the configured emojis", async () => {
+      using zellijSession = await launchZellijSession({
+        pluginConfig: { "status.failed": "❌", "alias.deploy": "🚢" },
+      });
+      const { session, configDir, cacheDir, sessionName } = zellijSession;
+      await session.press("esc");
+      await sleep(200);
+
+      await zellijAction(configDir, cacheDir, sessionName, "rename-tab", [
+        "TAB_A",
+      ]);
+      await zellijAction(configDir, cacheDir, sessionName, "new-tab");
+      await sleep(300);
+
+      await runPipe(
+        session,
+        configDir,
+        cacheDir,
+        sessionName,
+        "target=tab,tab_index=0,status=failed",
+      );
+      await runPipe(
+        session,
+        configDir,
+        cacheDir,
+        sessionName,
+        "target=tab,tab_index=0,emojis=:deploy:",
+      );
+      await sleep(300);
+
+      const tabNames = (
+        await queryTabNames(configDir, cacheDir, sessionName)
+      ).split("\n");
+      expect(tabNames).toContain("TAB_A | ❌ | 🚢");
+    }, 60000);
+  });
+
+  describe("when priority is given", () => {
+    test("should render high-priority emojis first", async () => {
+      const context = await setupSession();
+      const { session, configDir, cacheDir, sessionName } = context;
+
+      await zellijAction(configDir, cacheDir, sessionName, "rename-tab", [
+        "TAB_A",
+      ]);
+      await sleep(300);
+
+      await runPipe(
+        session,
+        configDir,
+        cacheDir,
+        sessionName,
+        "target=tab,emojis=📌💬,priority=low",
+      );
+      await runPipe(
+        session,
+        configDir,
+        cacheDir,
+        sessionName,
+        "target=tab,emojis=📌🧪",
+      );
+      await runPipe(
+        session,
+        configDir,
+        cacheDir,
+        sessionName,
+        "target=tab,emojis=📌🚨,priority=high",
+      );
+      await sleep(300);
+
+      const tabNames = (
+        await queryTabNames(configDir, cacheDir, sessionName)
+      ).split("\n");
+      expect(tabNames).toContain("TAB_A | 📌🚨 | 📌🧪 | 📌💬");
+    }, 60000);
+  });
+
+  describe("when an exclusive group is configured", () => {
+    test("should replace the other member of the group", async () => {
+      using zellijSession = await launchZellijSession({
+        pluginConfig: { "group.job": "⏳;✅;❌" },
+      });
+      const { session, configDir, cacheDir, sessionName } = zellijSession;
+      await session.press("esc");
+      await sleep(200);
+
+      await zellijAction(configDir, cacheDir, sessionName, "rename-tab", [
+        "TAB_A",
+      ]);
+      await sleep(300);
+
+      await runPipe(
+        session,
+        configDir,
+        cacheDir,
+        sessionName,
+        "target=tab,emojis=📌🚀",
+      );
+      await runPipe(
+        session,
+        configDir,
+        cacheDir,
+        sessionName,
+        "target=tab,emojis=📌⏳",
+      );
+      await runPipe(
+        session,
+        configDir,
+        cacheDir,
+        sessionName,
+        "target=tab,emojis=📌✅",
+      );
+      await sleep(300);
+
+      const tabNames = (
+        await queryTabNames(configDir, cacheDir, sessionName)
+      ).split("\n");
+      expect(tabNames).toContain("TAB_A | 📌🚀 | 📌✅");
+    }, 60000);
+
+    test("should only remove whole emojis of the group", async () => {
+      using zellijSession = await launchZellijSession({
+        pluginConfig: { "group.who": "👨;👩" },
+      });
+      const { session, configDir, cacheDir, sessionName } = zellijSession;
+      await session.press("esc");
+      await sleep(200);
+
+      await zellijAction(configDir, cacheDir, sessionName, "rename-tab", [
+        "TAB_A",
+      ]);
+      await sleep(300);
+
+      await runPipe(
+        session,
+        configDir,
+        cacheDir,
+        sessionName,
+        "target=tab,emojis=📌👨‍💻",
+      );
+      await runPipe(
+        session,
+        configDir,
+        cacheDir,
+        sessionName,
+        "target=tab,emojis=📌👨🚀",
+      );
+      await runPipe(
+        session,
+        configDir,
+        cacheDir,
+        sessionName,
+        "target=tab,emojis=📌👩",
+      );
+      await sleep(300);
+
+      const tabNames = (
+        await queryTabNames(configDir, cacheDir, sessionName)
+      ).split("\n");
+      expect(tabNames).toContain("TAB_A | 📌👨‍💻 | 📌🚀 | 📌👩");
+    }, 60000);
+  });
+
+  describe("when max_width is configured", () => {
+    test("should shorten the title but keep emojis, and restore it in full", async () => {
+      using zellijSession = await launchZellijSession({
+        pluginConfig: { max_width: "12" },
+      });
+      const { session, configDir, cacheDir, sessionName } = zellijSession;
+      await session.press("esc");
+      await sleep(200);
+
+      await zellijAction(configDir, cacheDir, sessionName, "rename-tab", [
+        "LONG TAB NAME",
+      ]);
+      await zellijAction(configDir, cacheDir, sessionName, "new-tab");
+      await sleep(300);
+
+      await runPipe(
+        session,
+        configDir,
+        cacheDir,
+        sessionName,
+        "target=tab,tab_index=0,emojis=🚀",
+      );
+      await sleep(300);
+
+      let tabNames = (
+        await queryTabNames(configDir, cacheDir, sessionName)
+      ).split("\n");
+      expect(tabNames).toContain("LONG T… | 🚀");
+
+      await zellijAction(configDir, cacheDir, sessionName, "go-to-tab", ["1"]);
+      await sleep(1500);
+
+      tabNames = (await queryTabNames(configDir, cacheDir, sessionName)).split(
+        "\n",
+      );
+      expect(tabNames).toContain("LONG TAB NAME");
+    }, 60000);
+  });
+
+  describe("when wait=true is given", () => {
+    test("should respond only after the new tab title is visible", async () => {
+      const context = await setupSession();
+      const { session, configDir, cacheDir, sessionName } = context;
+
+      await zellijAction(configDir, cacheDir, sessionName, "rename-tab", [
+        "TAB_A",
+      ]);
+      await sleep(300);
+
+      const output = await runPipe(
+        session,
+        configDir,
+        cacheDir,
+        sessionName,
+        "target=tab,emojis=📌⏳,wait=true",
+      );
+      expect(output).toBe("ok");
+
+      const tabNames = (
+        await queryTabNames(configDir, cacheDir, sessionName)
+      ).split("\n");
+      expect(tabNames).toContain("TAB_A | 📌⏳");
+    }, 30000);
+  });
+
+  describe("when progress= is given", () => {
+    test("should update the progress segment in place", async () => {
+      const context = await setupSession();
+      const { session, configDir, cacheDir, sessionName } = context;
+
+      await zellijAction(configDir, cacheDir, sessionName, "rename-tab", [
+        "TAB_A",
+      ]);
+      await zellijAction(configDir, cacheDir, sessionName, "new-tab");
+      await sleep(300);
+
+      await runPipe(
+        session,
+        configDir,
+        cacheDir,
+        sessionName,
+        "target=tab,tab_index=0,progress=25",
+      );
+      await runPipe(
+        session,
+        configDir,
+        cacheDir,
+        sessionName,
+        "target=tab,tab_index=0,emojis=📌🚀",
+      );
+      await sleep(300);
+
+      let tabNames = (
+        await queryTabNames(configDir, cacheDir, sessionName)
+      ).split("\n");
+      expect(tabNames).toContain("TAB_A | ▰▱▱▱ | 📌🚀");
+
+      await runPipe(
+        session,
+        configDir,
+        cacheDir,
+        sessionName,
+        "target=tab,tab_index=0,progress=75",
+      );
+      await sleep(300);
+
+      tabNames = (await queryTabNames(configDir, cacheDir, sessionName)).split(
+        "\n",
+      );
+      expect(tabNames).toContain("TAB_A | ▰▰▰▱ | 📌🚀");
+    }, 30000);
+
+    test("should keep the segment in place when it renders the same", async () => {
+      const context = await setupSession();
+      const { session, configDir, cacheDir, sessionName } = context;
+
+      await zellijAction(configDir, cacheDir, sessionName, "rename-tab", [
+        "TAB_A",
+      ]);
+      await zellijAction(configDir, cacheDir, sessionName, "new-tab");
+      await sleep(300);
+
+      await runPipe(
+        session,
+        configDir,
+        cacheDir,
+        sessionName,
+        "target=tab,tab_index=0,progress=40",
+      );
+      await runPipe(
+        session,
+        configDir,
+        cacheDir,
+        sessionName,
+        "target=tab,tab_index=0,emojis=📌🚀",
+      );
+      await runPipe(
+        session,
+        configDir,
+        cacheDir,
+        sessionName,
+        "target=tab,tab_index=0,progress=45",
+      );
+      await sleep(300);
+
+      const tabNames = (
+        await queryTabNames(configDir, cacheDir, sessionName)
+      ).split("\n");
+      expect(tabNames).toContain("TAB_A | ▰▰▱▱ | 📌🚀");
+    }, 30000);
+
+    test("should keep slots with equal values apart", async () => {
+      const context = await setupSession();
+      const { session, configDir, cacheDir, sessionName } = context;
+
+      await zellijAction(configDir, cacheDir, sessionName, "rename-tab", [
+        "TAB_A",
+      ]);
+      await zellijAction(configDir, cacheDir, sessionName, "new-tab");
+      await sleep(300);
+
+      await runPipe(
+        session,
+        configDir,
+        cacheDir,
+        sessionName,
+        "target=tab,tab_index=0,slot=a,progress=50",
+      );
+      await runPipe(
+        session,
+        configDir,
+        cacheDir,
+        sessionName,
+        "target=tab,tab_index=0,slot=b,progress=50",
+      );
+      await sleep(300);
+
+      let tabNames = (
+        await queryTabNames(configDir, cacheDir, sessionName)
+      ).split("\n");
+      expect(tabNames).toContain("TAB_A | ▰▰▱▱ | ▰▰▱▱");
+
+      await runPipe(
+        session,
+        configDir,
+        cacheDir,
+        sessionName,
+        "target=tab,tab_index=0,slot=a,progress=100",
+      );
+      await sleep(300);
+
+      tabNames = (await queryTabNames(configDir, cacheDir, sessionName)).split(
+        "\n",
+      );
+      expect(tabNames).toContain("TAB_A | ▰▰▰▰ | ▰▰▱▱");
+    }, 30000);
+
+    test("should clear a finished progress when the tab is visited", async () => {
+      const context = await setupSession();
+      const { session, configDir, cacheDir, sessionName } = context;
+
+      await zellijAction(configDir, cacheDir, sessionName, "rename-tab", [
+        "TAB_A",
+      ]);
+      await zellijAction(configDir, cacheDir, sessionName, "new-tab");
+      await sleep(300);
+
+      await runPipe(
+        session,
+        configDir,
+        cacheDir,
+        sessionName,
+        "target=tab,tab_index=0,progress=📌50",
+      );
+      await sleep(300);
+
+      let tabNames = (
+        await queryTabNames(configDir, cacheDir, sessionName)
+      ).split("\n");
+      expect(tabNames).toContain("TAB_A | 📌▰▰▱▱");
+
+      await runPipe(
+        session,
+        configDir,
+        cacheDir,
+        sessionName,
+        "target=tab,tab_index=0,progress=100",
+      );
+      await sleep(300);
+
+      tabNames = (await queryTabNames(configDir, cacheDir, sessionName)).split(
+        "\n",
+      );
+      expect(tabNames).toContain("TAB_A | ▰▰▰▰");
+
+      await zellijAction(configDir, cacheDir, sessionName, "go-to-tab", ["1"]);
+      await sleep(1500);
+
+      tabNames = (await queryTabNames(configDir, cacheDir, sessionName)).split(
+        "\n",
+      );
+      expect(tabNames).toContain("TAB_A");
+    }, 30000);
+  });
+
+  describe("when animate= is given", () => {
+    test("should keep the priority of the animated segment across frames", async () => {
+      const context = await setupSession();
+      const { session, configDir, cacheDir, sessionName } = context;
+
+      await zellijAction(configDir, cacheDir, sessionName, "rename-tab", [
+        "TAB_A",
+      ]);
+      await sleep(300);
+
+      await runPipe(
+        session,
+        configDir,
+        cacheDir,
+        sessionName,
+        "target=tab,emojis=📌🚀",
+      );
+      await runPipe(
+        session,
+        configDir,
+        cacheDir,
+        sessionName,
+        "target=tab,animate=📌🌑🌕,interval=200,priority=high",
+      );
+      await sleep(700);
+
+      await runPipe(
+        session,
+        configDir,
+        cacheDir,
+        sessionName,
+        "target=tab,emojis=📌🧪",
+      );
+      await sleep(300);
+
+      const tabNames = (
+        await queryTabNames(configDir, cacheDir, sessionName)
+      ).split("\n");
+      expect(
+        tabNames.some((name) =>
+          /^TAB_A \| 📌(🌑|🌕) \| 📌🚀 \| 📌🧪$/u.test(name),
+        ),
+      ).toBe(true);
+    }, 30000);
+  });
+
+  describe("when the tab holds only plugin panes", () => {
+    test("should apply emojis to the focused plugin-only tab", async () => {
+      const context = await setupSession();
+      const { configDir, cacheDir, sessionName } = context;
+
+      const layoutPath = join(configDir, "layouts", "plugin-only.kdl");
+      writeFileSync(
+        layoutPath,
+        `
+  layout {
+    pane {
+      plugin location="status-bar"
+    }
+  }
+  `,
+      );
+      await zellijAction(configDir, cacheDir, sessionName, "new-tab", [
+        `--layout=${layoutPath}`,
+      ]);
+      await sleep(300);
+      await zellijAction(configDir, cacheDir, sessionName, "rename-tab", [
+        "PLUGIN_ONLY",
+      ]);
+      await sleep(300);
+
+      await pinEmojiToTab({ context, emojis: "📌📊" });
+      await sleep(200);
+
+      const tabNames = (
+        await queryTabNames(configDir, cacheDir, sessionName)
+      ).split("\n");
+      expect(tabNames).toContain("PLUGIN_ONLY | 📌📊");
+    }, 60000);
+  });
+
   describe("when panes are deleted from tab", () => {
     const createTabWithMultiplePanes = async ({
       configDir,
@@ -10,6 +10,8 @@ const PIPE_PAYLOAD = "_";
 
 type SetupConfigOptions = {
   wasmPath?: string;
+  loadPlugin?: boolean;
+  pluginConfig?: Record<string, string>;
   simplifiedUi?: boolean;
   showStartupTips?: boolean;
 };
@@ -26,19 +28,25 @@ export function setupConfigDir(options: SetupConfigOptions = {}): string {
   mkdirSync(configDir, { recursive: true });
   mkdirSync(join(configDir, "layouts"), { recursive: true });
 
+  const pluginConfig = Object.entries(options.pluginConfig ?? {})
+    .map(([key, value]) => `    ${key} "${value}"`)
+    .join("\n");
   const plugins = `
 plugins {
-  emotitle location="file:${options.wasmPath}"
+  emotitle location="file:${options.wasmPath}" {
+${pluginConfig}
+  }
 }
 `;
 
-  const loadPlugins = options.wasmPath
-    ? `
+  const loadPlugins =
+    options.wasmPath && options.loadPlugin !== false
+      ? `
 load_plugins {
   emotitle
 }
 `
-    : "";
+      : "";
   const uiConfig = options.simplifiedUi
     ? `
 ui {
@@ -124,9 +132,16 @@ export function cleanupCacheDir(cacheDir: string): void {
   } catch {}
 }
 
-export async function launchZellijSession() {
+type LaunchOptions = {
+  loadPlugin?: boolean;
+  pluginConfig?: Record<string, string>;
+};
+
+export async function launchZellijSession(options: LaunchOptions = {}) {
   const configDir = setupConfigDir({
     wasmPath: WASM_PATH,
+    loadPlugin: options.loadPlugin,
+    pluginConfig: options.pluginConfig,
     simplifiedUi: true,
     showStartupTips: false,
   });
@@ -0,0 +1,108 @@
+use std::time::{Duration, Instant};
+
+use unicode_segmentation::UnicodeSegmentation;
+
+pub const DEFAULT_FRAME_INTERVAL: Duration = Duration::from_millis(500);
+pub const MIN_FRAME_INTERVAL: Duration = Duration::from_millis(100);
+pub const MAX_FRAME_INTERVAL: Duration = Duration::from_secs(60);
+
+const SPINNERS: &[(&str, &[&str])] = &[
+    ("hourglass", &["⏳", "⌛"]),
+    ("moon", &["🌑", "🌒", "🌓", "🌔", "🌕", "🌖", "🌗", "🌘"]),
+    (
+        "clock",
+        &[
+            "🕛", "🕐", "🕑", "🕒", "🕓", "🕔", "🕕", "🕖", "🕗", "🕘", "🕙", "🕚",
+        ],
+    ),
+    ("dots", &["⠋", "⠙", "⠹", "⠸", "⠼", "⠴", "⠦", "⠧", "⠇", "⠏"]),
+];
+
+/// Frames for `animate=`: a spinner name, a `;`-separated list, or a run of
+/// emojis taken one grapheme per frame.
+pub fn parse_frames(value: &str) -> Result<Vec<String>, String> {
+    let value = value.trim();
+    let frames: Vec<String> =
+        if let Some((_, frames)) = SPINNERS.iter().find(|(name, _)| *name == value) {
+            frames.iter().map(|frame| frame.to_string()).collect()
+        } else if value.contains(';') {
+            value
+                .split(';')
+                .map(str::trim)
+                .filter(|frame| !frame.is_empty())
+                .map(str::to_string)
+                .collect()
+        } else {
+            value.graphemes(true).map(str::to_string).collect()
+        };
+
+    if frames.len() < 2 {
+        let names: Vec<&str> = SPINNERS.iter().map(|(name, _)| *name).collect();
+        return Err(format!(
+            "animate needs at least two frames or a spinner name ({})",
+            names.join(", ")
+        ));
+    }
+    Ok(frames)
+}
+
+/// A decoration segment cycling through `frames`. It keeps running while
+/// its slot on the target shows one of its frames and stops once that
+/// segment is gone or replaced.
+#[derive(Debug, Clone)]
+pub struct Animation {
+    frames: Vec<String>,
+    pinned: bool,
+    interval: Duration,
+    slot: Option<String>,
+    frame: usize,
+    next_at: Instant,
+}
+
+impl Animation {
+    pub fn new(
+        frames: Vec<String>,
+        pinned: bool,
+        interval: Duration,
+        slot: Option<String>,
+    ) -> Self {
+        Self {
+            frames,
+            pinned,
+            interval,
+            slot,
+            frame: 0,
+            next_at: Instant::now() + interval,
+        }
+    }
+
+    pub fn next_at(&self) -> Instant {
+        self.next_at
+    }
+
+    pub fn slot(&self) -> Option<&str> {
+        self.slot.as_deref()
+    }
+
+    /// Whether `segment` shows one of the frames.
+    pub fn shows(&self, segment: &str) -> bool {
+        let frame = if self.pinned {
+            segment.strip_prefix('📌')
+        } else {
+            Some(segment)
+        };
+        frame.is_some_and(|frame| self.frames.iter().any(|known| known == frame))
+    }
+
+    /// Moves to the next frame and returns the segment that shows it.
+    pub fn advance(&mut self, now: Instant) -> String {
+        self.frame = (self.frame + 1) % self.frames.len();
+        self.next_at = now + self.interval;
+        let frame = &self.frames[self.frame];
+        if self.pinned {
+            format!("📌{frame}")
+        } else {
+            frame.clone()
+        }
+    }
+}
@@ -1,6 +1,18 @@
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, HashSet};
+use std::time::Duration;
 
-fn replace_colon_emoji(s: &str) -> String {
+use crate::animation::{
+    parse_frames, DEFAULT_FRAME_INTERVAL, MAX_FRAME_INTERVAL, MIN_FRAME_INTERVAL,
+};
+use crate::config::Config;
+use crate::progress::{parse_percent, ProgressStyle};
+
+const MAX_TARGETS: usize = 256;
+const DEFAULT_PROGRESS_SLOT: &str = "progress";
+const DEFAULT_ANIMATION_SLOT: &str = "animate";
+
+/// Expands `:shortcode:`s, trying the configured aliases before gemoji.
+fn replace_colon_emoji(s: &str, aliases: &BTreeMap<String, String>) -> String {
     let mut result = String::new();
     let mut rest = s;
 
@@ -9,10 +21,15 @@ fn replace_colon_emoji(s: &str) -> String {
         .map(|i| (i, i + 1))
         .and_then(|(i, m)| rest[m..].find(':').map(|x| (i, m, m + x, m + x + 1)))
     {
-        match emojis::get_by_shortcode(&rest[m..n]) {
+        let shortcode = &rest[m..n];
+        match aliases
+            .get(shortcode)
+            .map(String::as_str)
+            .or_else(|| emojis::get_by_shortcode(shortcode).map(|emoji| emoji.as_str()))
+        {
             Some(emoji) => {
                 result.push_str(&rest[..i]);
-                result.push_str(emoji.as_str());
+                result.push_str(emoji);
                 rest = &rest[j..];
             }
             None => {
@@ -31,14 +48,36 @@ pub enum Mode {
     Permanent,
 }
 
+/// Decorations render highest priority first, and low-priority ones are the
+/// first to go when a title runs out of room.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Priority {
+    Low,
+    #[default]
+    Normal,
+    High,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TargetKind {
+    Pane,
+    Tab,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum Target {
     Pane {
-        pane_id: Option<u32>,
+        pane_ids: Vec<u32>,
     },
     Tab {
-        pane_id: Option<u32>,
-        tab_index: Option<usize>,
+        pane_ids: Vec<u32>,
+        tab_indices: Vec<usize>,
+    },
+    All {
+        kind: TargetKind,
+    },
+    Unfocused {
+        kind: TargetKind,
     },
 }
 
@@ -47,48 +86,189 @@ pub struct Command {
     pub target: Target,
     pub emojis: String,
     pub mode: Mode,
+    pub priority: Priority,
+    /// Frames `emojis` cycles through, starting with the one it shows.
+    pub animate: Option<Vec<String>>,
+    pub interval: Duration,
+    /// Named slot whose segment this command updates in place.
+    pub slot: Option<String>,
+    pub floating: Option<bool>,
+    pub wait: bool,
+    pub follow: bool,
     pub trace: bool,
 }
 
-pub fn parse_args(args: &BTreeMap<String, String>) -> Result<Command, String> {
+pub fn parse_args(args: &BTreeMap<String, String>, config: &Config) -> Result<Command, String> {
     let target = args
         .get("target")
         .ok_or_else(|| "missing required arg: target".to_string())?;
-    let emojis = replace_colon_emoji(
-        args.get("emojis")
-            .ok_or_else(|| "missing required arg: emojis".to_string())?
-            .trim(),
-    );
+    let sources: Vec<&str> = ["emojis", "status", "animate", "progress"]
+        .into_iter()
+        .filter(|key| args.contains_key(*key))
+        .collect();
+    if sources.len() > 1 {
+        return Err(format!("{} cannot be set together", sources.join(" and ")));
+    }
+    let mut animate = None;
+    let emojis = if let Some(emojis) = args.get("emojis") {
+        replace_colon_emoji(emojis.trim(), &config.aliases)
+    } else if let Some(status) = args.get("status") {
+        replace_colon_emoji(status_emojis(status, config)?.trim(), &config.aliases)
+    } else if let Some(value) = args.get("animate") {
+        let value = replace_colon_emoji(value.trim(), &config.aliases);
+        let (pin, frames) = match value.strip_prefix('📌') {
+            Some(frames) => ("📌", frames),
+            None => ("", value.as_str()),
+        };
+        let frames = parse_frames(frames)?;
+        let emojis = format!("{pin}{}", frames[0]);
+        animate = Some(frames);
+        emojis
+    } else if let Some(percent) = args.get("progress") {
+        let style = args
+            .get("style")
+            .map(|style| ProgressStyle::parse(style))
+            .transpose()?
+            .unwrap_or(ProgressStyle::Bar);
+        let (pin, percent) = match percent.trim().strip_prefix('📌') {
+            Some(percent) => ("📌", percent),
+            None => ("", percent.as_str()),
+        };
+        format!("{pin}{}", style.render(parse_percent(percent)?))
+    } else {
+        return Err("missing required arg: emojis".to_string());
+    };
     if emojis.is_empty() {
         return Err("emojis must not be empty".to_string());
     }
 
-    let mode = mode_from_emojis(&emojis);
+    let mode = mode_from_emojis(&emojis, config);
+    let emojis = match mode {
+        Mode::Permanent if !emojis.starts_with('📌') => format!("📌{emojis}"),
+        _ => emojis,
+    };
 
     let command_target = match target.as_str() {
         "pane" => {
-            let pane_id = parse_optional_u32(args.get("pane_id"), "pane_id")?;
+            let pane_ids = parse_id_list(args.get("pane_id"), "pane_id")?;
             if args.contains_key("tab_index") {
                 return Err("tab_index is not allowed when target=pane".to_string());
             }
-            Target::Pane { pane_id }
+            Target::Pane { pane_ids }
         }
         "tab" => {
-            let pane_id = parse_optional_u32(args.get("pane_id"), "pane_id")?;
-            let tab_index = parse_optional_usize(args.get("tab_index"), "tab_index")?;
+            let pane_ids = parse_id_list(args.get("pane_id"), "pane_id")?;
+            let tab_indices = parse_id_list(args.get("tab_index"), "tab_index")?
+                .into_iter()
+                .map(|index| index as usize)
+                .collect::<Vec<_>>();
             if args.contains_key("tab_position") {
                 return Err("tab_position is no longer supported; use tab_index".to_string());
             }
-            if pane_id.is_some() && tab_index.is_some() {
+            if !pane_ids.is_empty() && !tab_indices.is_empty() {
                 return Err(
                     "pane_id and tab_index cannot be set together when target=tab".to_string(),
                 );
             }
-            Target::Tab { pane_id, tab_index }
+            Target::Tab {
+                pane_ids,
+                tab_indices,
+            }
+        }
+        "all" | "unfocused" => {
+            for key in ["pane_id", "tab_index"] {
+                if args.contains_key(key) {
+                    return Err(format!("{key} is not allowed when target={target}"));
+                }
+            }
+            let kind = match args.get("kind").map(String::as_str) {
+                None | Some("tab") => TargetKind::Tab,
+                Some("pane") => TargetKind::Pane,
+                Some(other) => return Err(format!("unsupported kind: {other}")),
+            };
+            if target == "all" {
+                Target::All { kind }
+            } else {
+                Target::Unfocused { kind }
+            }
         }
         other => return Err(format!("unsupported target: {other}")),
     };
 
+    let floating = match args.get("floating").map(String::as_str) {
+        None => None,
+        Some("true") | Some("1") => Some(true),
+        Some("false") | Some("0") => Some(false),
+        Some(_) => return Err("floating must be true or false".to_string()),
+    };
+    if floating.is_some() {
+        match &command_target {
+            Target::Pane { pane_ids } if pane_ids.is_empty() => {}
+            Target::Pane { .. } => {
+                return Err("floating cannot be set together with pane_id".to_string());
+            }
+            Target::All { kind } | Target::Unfocused { kind } if *kind == TargetKind::Pane => {}
+            _ => return Err("floating is only allowed for pane targets".to_string()),
+        }
+    }
+
+    let wait = match args.get("wait").map(String::as_str) {
+        None | Some("false") | Some("0") => false,
+        Some("true") | Some("1") => true,
+        Some(_) => return Err("wait must be true or false".to_string()),
+    };
+
+    let follow = match args.get("follow").map(String::as_str) {
+        None | Some("false") | Some("0") => false,
+        Some("true") | Some("1") => true,
+        Some(_) => return Err("follow must be true or false".to_string()),
+    };
+    if follow {
+        match &command_target {
+            Target::Pane { .. } => {}
+            Target::All { kind } | Target::Unfocused { kind } if *kind == TargetKind::Pane => {}
+            _ => return Err("follow is only allowed for pane targets".to_string()),
+        }
+    }
+
+    if args.contains_key("style") && !args.contains_key("progress") {
+        return Err("style is only allowed with progress".to_string());
+    }
+    let slot = match args.get("slot").map(|slot| slot.trim()) {
+        Some("") => return Err("slot must not be empty".to_string()),
+        Some(slot) => Some(slot.to_string()),
+        None if args.contains_key("progress") => Some(DEFAULT_PROGRESS_SLOT.to_string()),
+        None if animate.is_some() => Some(DEFAULT_ANIMATION_SLOT.to_string()),
+        None => None,
+    };
+
+    let interval = match args.get("interval") {
+        None => DEFAULT_FRAME_INTERVAL,
+        Some(_) if animate.is_none() => {
+            return Err("interval is only allowed with animate".to_string());
+        }
+        Some(value) => value
+            .trim()
+            .parse::<u64>()
+            .ok()
+            .map(Duration::from_millis)
+            .filter(|interval| (MIN_FRAME_INTERVAL..=MAX_FRAME_INTERVAL).contains(interval))
+            .ok_or_else(|| {
+                format!(
+                    "interval must be a number of milliseconds between {} and {}",
+                    MIN_FRAME_INTERVAL.as_millis(),
+                    MAX_FRAME_INTERVAL.as_millis()
+                )
+            })?,
+    };
+
+    let priority = match args.get("priority").map(String::as_str) {
+        None | Some("normal") => Priority::Normal,
+        Some("high") => Priority::High,
+        Some("low") => Priority::Low,
+        Some(_) => return Err("priority must be high, normal or low".to_string()),
+    };
+
     let trace = args
         .get("trace")
         .map(|v| v == "1" || v == "true")
@@ -98,34 +278,72 @@ pub fn parse_args(args: &BTreeMap<String, String>) -> Result<Command, String> {
         target: command_target,
         emojis,
         mode,
+        priority,
+        animate,
+        interval,
+        slot,
+        floating,
+        wait,
+        follow,
         trace,
     })
 }
 
-fn mode_from_emojis(emojis: &str) -> Mode {
+fn status_emojis<'a>(status: &str, config: &'a Config) -> Result<&'a String, String> {
+    config.statuses.get(status.trim()).ok_or_else(|| {
+        if config.statuses.is_empty() {
+            format!("unknown status: {status}; no status.* entries are configured")
+        } else {
+            let known: Vec<&str> = config.statuses.keys().map(String::as_str).collect();
+            format!(
+                "unknown status: {status}; expected one of {}",
+                known.join(", ")
+            )
+        }
+    })
+}
+
+/// A leading `📌` always pins; otherwise the configured rules and default
+/// mode decide, and a permanent result gets the `📌` added.
+fn mode_from_emojis(emojis: &str, config: &Config) -> Mode {
     if emojis.starts_with('📌') {
         Mode::Permanent
     } else {
-        Mode::Temp
+        config.mode_for(emojis)
     }
 }
 
-fn parse_optional_u32(value: Option<&String>, key: &str) -> Result<Option<u32>, String> {
-    match value {
-        None => Ok(None),
-        Some(v) => v
-            .parse::<u32>()
-            .map(Some)
-            .map_err(|_| format!("{key} must be an unsigned integer")),
+/// Parses `3`, `3;7;12`, `0..3` (inclusive) or any `;`-separated mix of them.
+/// Duplicates are dropped while keeping the first occurrence order.
+fn parse_id_list(value: Option<&String>, key: &str) -> Result<Vec<u32>, String> {
+    let Some(value) = value else {
+        return Ok(Vec::new());
+    };
+
+    let invalid =
+        || format!("{key} must be an unsigned integer, a `;`-separated list or a range like 0..3");
+
+    let mut ids = Vec::new();
+    for item in value.split(';').map(str::trim) {
+        if let Some((start, end)) = item.split_once("..") {
+            let start = start.trim().parse::<u32>().map_err(|_| invalid())?;
+            let end = end.trim().parse::<u32>().map_err(|_| invalid())?;
+            if start > end {
+                return Err(format!("{key} range {item} must not be descending"));
+            }
+            if (end - start) as usize >= MAX_TARGETS {
+                return Err(format!("{key} range {item} exceeds {MAX_TARGETS} targets"));
+            }
+            ids.extend(start..=end);
+        } else {
+            ids.push(item.parse::<u32>().map_err(|_| invalid())?);
+        }
     }
-}
 
-fn parse_optional_usize(value: Option<&String>, key: &str) -> Result<Option<usize>, String> {
-    match value {
-        None => Ok(None),
-        Some(v) => v
-            .parse::<usize>()
-            .map(Some)
-            .map_err(|_| format!("{key} must be an unsigned integer")),
+    let mut seen = HashSet::new();
+    ids.retain(|id| seen.insert(*id));
+    if ids.len() > MAX_TARGETS {
+        return Err(format!("{key} exceeds {MAX_TARGETS} targets"));
     }
+    Ok(ids)
 }
@@ -0,0 +1,262 @@
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::command::Mode;
+use crate::state::{TitleFormat, DEFAULT_SEPARATOR, DEFAULT_TITLE_TEMPLATE};
+
+const DEFAULT_PIPE_NAME: &str = "emotitle";
+const DEFAULT_RESTORE_DELAY: Duration = Duration::from_secs(1);
+const DEFAULT_QUEUE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Plugin configuration from `config.kdl` or a layout. Invalid entries fall
+/// back to their defaults and are reported in `errors` instead of panicking.
+#[derive(Debug, Clone)]
+pub struct Config {
+    /// Layout of decorated pane titles (`pane_template` and `separator`).
+    pub pane_format: TitleFormat,
+    /// Layout of decorated tab names (`tab_template` and `separator`).
+    pub tab_format: TitleFormat,
+    /// How long a temporary decoration stays after its target gets the focus.
+    pub restore_delay: Duration,
+    /// How long the user must stay on a target before its decoration clears.
+    pub min_dwell: Duration,
+    /// How long commands wait for the first `PaneUpdate`/`TabUpdate`.
+    pub queue_timeout: Duration,
+    /// Pipe names the plugin answers to.
+    pub pipe_names: Vec<String>,
+    /// Mode for emojis without a `📌` prefix or a matching rule.
+    pub default_mode: Mode,
+    /// `alias.deploy "🚢"` makes `:deploy:` expand to 🚢.
+    pub aliases: BTreeMap<String, String>,
+    /// `status.failed "❌"` makes `status=failed` decorate with ❌.
+    pub statuses: BTreeMap<String, String>,
+    /// `rule.🚨 "permanent"` decides the mode of commands carrying 🚨.
+    pub rules: Vec<(String, Mode)>,
+    /// `group.job "⏳;✅;❌"`: applying one member removes the others.
+    pub groups: BTreeMap<String, Vec<String>>,
+    pub errors: Vec<String>,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            pane_format: TitleFormat::default(),
+            tab_format: TitleFormat::default(),
+            restore_delay: DEFAULT_RESTORE_DELAY,
+            min_dwell: Duration::ZERO,
+            queue_timeout: DEFAULT_QUEUE_TIMEOUT,
+            pipe_names: vec![DEFAULT_PIPE_NAME.to_string()],
+            default_mode: Mode::Temp,
+            aliases: BTreeMap::new(),
+            statuses: BTreeMap::new(),
+            rules: Vec::new(),
+            groups: BTreeMap::new(),
+            errors: Vec::new(),
+        }
+    }
+}
+
+impl Config {
+    pub fn parse(configuration: &BTreeMap<String, String>) -> Self {
+        let mut config = Config::default();
+        let mut separator = DEFAULT_SEPARATOR.to_string();
+        let mut pane_template = DEFAULT_TITLE_TEMPLATE.to_string();
+        let mut tab_template = DEFAULT_TITLE_TEMPLATE.to_string();
+        let mut max_width = None;
+        let mut max_segments = None;
+
+        for (key, value) in configuration {
+            let result = match key.as_str() {
+                "separator" => parse_separator(value).map(|value| separator = value),
+                "pane_template" => {
+                    pane_template = value.clone();
+                    Ok(())
+                }
+                "max_width" => {
+                    parse_positive(key, value, "columns").map(|width| max_width = Some(width))
+                }
+                "max_segments" => parse_positive(key, value, "segments")
+                    .map(|segments| max_segments = Some(segments)),
+                "tab_template" => {
+                    tab_template = value.clone();
+                    Ok(())
+                }
+                "restore_delay" => {
+                    parse_seconds(key, value).map(|delay| config.restore_delay = delay)
+                }
+                "min_dwell" => parse_seconds(key, value).map(|dwell| config.min_dwell = dwell),
+                "queue_timeout" => {
+                    parse_seconds(key, value).map(|timeout| config.queue_timeout = timeout)
+                }
+                "pipe_names" => parse_pipe_names(value).map(|names| config.pipe_names = names),
+                "default_mode" => parse_mode(key, value).map(|mode| config.default_mode = mode),
+                _ => {
+                    if let Some(name) = key.strip_prefix("alias.") {
+                        parse_named_emoji(key, name, value).map(|emoji| {
+                            config.aliases.insert(name.to_string(), emoji);
+                        })
+                    } else if let Some(name) = key.strip_prefix("status.") {
+                        parse_named_emoji(key, name, value).map(|emoji| {
+                            config.statuses.insert(name.to_string(), emoji);
+                        })
+                    } else if let Some(name) = key.strip_prefix("group.") {
+                        parse_group(key, value).map(|members| {
+                            config.groups.insert(name.to_string(), members);
+                        })
+                    } else if let Some(emoji) = key.strip_prefix("rule.") {
+                        parse_mode(key, value)
+                            .map(|mode| config.rules.push((emoji.to_string(), mode)))
+                    } else {
+                        Err(format!("unknown config key: {key}"))
+                    }
+                }
+            };
+            if let Err(err) = result {
+                config.errors.push(err);
+            }
+        }
+
+        config.pane_format = parse_template("pane_template", &pane_template, &separator)
+            .unwrap_or_else(|err| {
+                config.errors.push(err);
+                TitleFormat::new(DEFAULT_TITLE_TEMPLATE, &separator)
+                    .expect("default title template is valid")
+            });
+        config.tab_format = parse_template("tab_template", &tab_template, &separator)
+            .unwrap_or_else(|err| {
+                config.errors.push(err);
+                TitleFormat::new(DEFAULT_TITLE_TEMPLATE, &separator)
+                    .expect("default title template is valid")
+            });
+        config.pane_format.max_width = max_width;
+        config.tab_format.max_width = max_width;
+        config.pane_format.max_segments = max_segments;
+        config.tab_format.max_segments = max_segments;
+
+        config
+    }
+
+    pub fn accepts_pipe(&self, name: &str) -> bool {
+        self.pipe_names.iter().any(|pipe_name| pipe_name == name)
+    }
+
+    /// Members of every group `emojis` belongs to, which must no longer
+    /// decorate a target once `emojis` is applied to it.
+    pub fn exclusive_with(&self, emojis: &str) -> Vec<String> {
+        self.groups
+            .values()
+            .filter(|members| {
+                members
+                    .iter()
+                    .any(|member| contains_graphemes(emojis, member))
+            })
+            .flatten()
+            .cloned()
+            .collect()
+    }
+
+    /// Mode for `emojis` that carry no explicit `📌`: the first rule whose
+    /// emoji they contain, otherwise `default_mode`.
+    pub fn mode_for(&self, emojis: &str) -> Mode {
+        self.rules
+            .iter()
+            .find(|(emoji, _)| contains_graphemes(emojis, emoji))
+            .map(|(_, mode)| *mode)
+            .unwrap_or(self.default_mode)
+    }
+}
+
+fn parse_separator(value: &str) -> Result<String, String> {
+    if value.trim().is_empty() {
+        return Err("separator must contain a visible character".to_string());
+    }
+    // The separator is spliced into the templates, where a placeholder in it
+    // would turn even the default template invalid.
+    if value.contains("{title}") || value.contains("{emojis}") {
+        return Err(format!(
+            "separator must not contain {{title}} or {{emojis}}, got {value:?}"
+        ));
+    }
+    Ok(value.to_string())
+}
+
+fn parse_template(key: &str, template: &str, separator: &str) -> Result<TitleFormat, String> {
+    TitleFormat::new(template, separator).map_err(|err| format!("{key}: {err}"))
+}
+
+fn parse_positive(key: &str, value: &str, unit: &str) -> Result<usize, String> {
+    value
+        .trim()
+        .parse::<usize>()
+        .ok()
+        .filter(|width| *width > 0)
+        .ok_or_else(|| format!("{key} must be a positive number of {unit}, got {value:?}"))
+}
+
+fn parse_seconds(key: &str, value: &str) -> Result<Duration, String> {
+    value
+        .trim()
+        .parse::<f64>()
+        .ok()
+        .and_then(|seconds| Duration::try_from_secs_f64(seconds).ok())
+        .ok_or_else(|| format!("{key} must be a non-negative number of seconds, got {value:?}"))
+}
+
+fn parse_pipe_names(value: &str) -> Result<Vec<String>, String> {
+    let names: Vec<String> = value
+        .split(';')
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+        .map(str::to_string)
+        .collect();
+    if names.is_empty() {
+        return Err("pipe_names must name at least one pipe".to_string());
+    }
+    Ok(names)
+}
+
+fn parse_mode(key: &str, value: &str) -> Result<Mode, String> {
+    match value.trim() {
+        "temp" | "temporary" => Ok(Mode::Temp),
+        "permanent" | "pinned" => Ok(Mode::Permanent),
+        other => Err(format!("{key} must be temp or permanent, got {other:?}")),
+    }
+}
+
+/// Whether `member` occurs in `emojis` as whole graphemes, so `👨` is not
+/// found in `👨‍💻`.
+fn contains_graphemes(emojis: &str, member: &str) -> bool {
+    let emojis: Vec<&str> = emojis.graphemes(true).collect();
+    let member: Vec<&str> = member.graphemes(true).collect();
+    !member.is_empty()
+        && emojis
+            .windows(member.len())
+            .any(|window| window == member.as_slice())
+}
+
+fn parse_group(key: &str, value: &str) -> Result<Vec<String>, String> {
+    let members: Vec<String> = value
+        .split(';')
+        .map(str::trim)
+        .filter(|member| !member.is_empty())
+        .map(str::to_string)
+        .collect();
+    if members.len() < 2 {
+        return Err(format!(
+            "{key} must list at least two `;`-separated emojis, got {value:?}"
+        ));
+    }
+    Ok(members)
+}
+
+fn parse_named_emoji(key: &str, name: &str, value: &str) -> Result<String, String> {
+    if name.is_empty() || name.contains(':') {
+        return Err(format!("{key} does not have a valid name"));
+    }
+    if value.trim().is_empty() {
+        return Err(format!("{key} must not be empty"));
+    }
+    Ok(value.trim().to_string())
+}
@@ -1,57 +1,140 @@
+mod animation;
 mod command;
+mod config;
+mod pending_ack;
+mod progress;
+mod scheduler;
 mod state;
 mod tab_index_tracker;
 
 use std::collections::BTreeMap;
+use std::time::Instant;
 
-use command::{parse_args, Command, Mode, Target};
-use state::{
-    extract_original_title, title_with_emojis, title_with_pinned_segments, EmotitleState, PaneRef,
-};
+use animation::{Animation, MIN_FRAME_INTERVAL};
+use command::{parse_args, Command, Mode, Target, TargetKind};
+use config::Config;
+use pending_ack::{Applied, Expectation, PendingAck, WAIT_TIMEOUT};
+use scheduler::{DeadlineKind, Scheduler, TIMER_SLACK};
+use state::{Decoration, EmotitleState, PaneRef, PermissionState, TAB_RENAME_CHECK_TIMEOUT};
 use zellij_tile::prelude::*;
 
 register_plugin!(PluginState);
 
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum ResolvedTarget {
+    Pane(PaneRef),
+    Tab(usize),
+}
+
+impl ResolvedTarget {
+    fn label(&self) -> String {
+        match self {
+            ResolvedTarget::Pane(PaneRef::Terminal(id)) => format!("pane_id={id}"),
+            ResolvedTarget::Pane(PaneRef::Plugin(id)) => format!("plugin_pane_id={id}"),
+            ResolvedTarget::Tab(tab_index) => format!("tab_index={tab_index}"),
+        }
+    }
+}
+
+const REQUIRED_PERMISSIONS: [PermissionType; 3] = [
+    PermissionType::ReadApplicationState,
+    PermissionType::ChangeApplicationState,
+    PermissionType::ReadCliPipes,
+];
+
+/// At most this many animation frames are renamed per timer tick.
+const MAX_ANIMATION_RENAMES_PER_TICK: usize = 8;
+
+/// A command that arrived before the first `PaneUpdate`/`TabUpdate`, e.g.
+/// the one whose `zellij pipe` auto-started the plugin.
+struct QueuedCommand {
+    command: Command,
+    pipe_message: PipeMessage,
+    deadline: Instant,
+}
+
 #[derive(Default)]
 struct PluginState {
     state: EmotitleState,
+    pending_acks: Vec<PendingAck>,
+    queued_commands: Vec<QueuedCommand>,
+    config: Config,
+    /// Config errors are prepended to the first pipe response only.
+    config_errors_reported: bool,
+    scheduler: Scheduler,
 }
 
 impl ZellijPlugin for PluginState {
-    fn load(&mut self, _configuration: BTreeMap<String, String>) {
-        request_permission(&[
-            PermissionType::ReadApplicationState,
-            PermissionType::ChangeApplicationState,
-            PermissionType::ReadCliPipes,
-        ]);
+    fn load(&mut self, configuration: BTreeMap<String, String>) {
+        self.config = Config::parse(&configuration);
+        self.state.pane_format = self.config.pane_format.clone();
+        self.state.tab_format = self.config.tab_format.clone();
+        self.state.config_errors = self.config.errors.clone();
+        self.state.restore_delay = self.config.restore_delay;
+        self.state.min_dwell = self.config.min_dwell;
+        request_permission(&REQUIRED_PERMISSIONS);
         subscribe(&[
             EventType::PaneUpdate,
             EventType::TabUpdate,
             EventType::Timer,
+            EventType::PermissionRequestResult,
         ]);
         set_selectable(false);
     }
 
     fn update(&mut self, event: Event) -> bool {
         match event {
+            Event::PermissionRequestResult(PermissionStatus::Granted) => {
+                self.state.permission = PermissionState::Granted;
+                self.replay_queued_commands();
+            }
+            Event::PermissionRequestResult(PermissionStatus::Denied) => {
+                self.state.permission = PermissionState::Denied;
+                self.reject_queued_commands(&missing_permissions_error());
+            }
             Event::PaneUpdate(pane_manifest) => {
-                if self.state.update_pane_manifest(pane_manifest) {
-                    set_timeout(1.0);
+                self.state.permission = PermissionState::Granted;
+                let restore_pending = self.state.update_pane_manifest(pane_manifest);
+                if restore_pending {
+                    self.schedule_restore();
                 }
+                for (pane_ref, title) in self.state.sync_followed_panes() {
+                    self.state.expect_pane_echo(pane_ref.clone(), &title);
+                    rename_pane(&pane_ref, title);
+                }
+                self.uncover_followed_panes();
+                self.replay_queued_commands();
+                self.settle_pending_acks();
             }
             Event::TabUpdate(tab_infos) => {
-                if self.state.update_tab_infos(tab_infos) {
-                    set_timeout(1.0);
+                self.state.permission = PermissionState::Granted;
+                let restore_pending = self.state.update_tab_infos(tab_infos);
+                if restore_pending {
+                    self.schedule_restore();
+                }
+                self.uncover_followed_panes();
+                self.apply_tab_rename_checks();
+                self.replay_queued_commands();
+                self.settle_pending_acks();
+            }
+            Event::Timer(_seconds) => {
+                for kind in self.scheduler.take_due() {
+                    match kind {
+                        DeadlineKind::Restore => self.apply_pending_restores(),
+                        DeadlineKind::TabRenameCheck => self.apply_tab_rename_checks(),
+                        DeadlineKind::WaitTimeout => self.settle_pending_acks(),
+                        DeadlineKind::QueueTimeout => self.expire_queued_commands(),
+                        DeadlineKind::Animate => self.advance_animations(),
+                    }
                 }
             }
-            Event::Timer(_seconds) => self.apply_pending_restores(),
             _ => {}
         }
         false
     }
 
     fn pipe(&mut self, pipe_message: PipeMessage) -> bool {
-        if pipe_message.name != "emotitle" {
+        if !self.config.accepts_pipe(&pipe_message.name) {
             return false;
         }
 
@@ -61,11 +144,17 @@ impl ZellijPlugin for PluginState {
             return false;
         }
 
-        match parse_args(args) {
-            Ok(command) => match self.handle_command(command, &pipe_message) {
-                Ok(()) => print_to_pipe(&pipe_message, "ok"),
-                Err(err) => print_to_pipe(&pipe_message, &err),
-            },
+        self.report_config_errors(&pipe_message);
+        match parse_args(args, &self.config) {
+            Ok(_) if self.state.permission == PermissionState::Denied => {
+                print_to_pipe(&pipe_message, &missing_permissions_error());
+            }
+            Ok(command) if !self.state.has_received_updates() => {
+                self.queue_command(command, pipe_message);
+            }
+            Ok(command) => {
+                self.run_command(command, &pipe_message);
+            }
             Err(err) => {
                 print_to_pipe(&pipe_message, &err);
             }
@@ -77,21 +166,236 @@ impl ZellijPlugin for PluginState {
 }
 
 impl PluginState {
+    /// Applies `command` and responds, unless the response is held back for
+    /// `wait=true`. Returns whether the CLI pipe is left blocked.
+    fn run_command(&mut self, command: Command, pipe_message: &PipeMessage) -> bool {
+        let wait = command.wait;
+        let results = self.handle_command(command, pipe_message);
+        match &pipe_message.source {
+            PipeSource::Cli(pipe_id) if wait => self.wait_for_titles(pipe_id, results),
+            _ => {
+                let results: Vec<_> = results
+                    .into_iter()
+                    .map(|(label, result)| (label, result.map(|applied| applied.summary())))
+                    .collect();
+                print_to_pipe(pipe_message, &format_results(&results));
+                false
+            }
+        }
+    }
+
+    fn report_config_errors(&mut self, pipe_message: &PipeMessage) {
+        if self.config_errors_reported || self.config.errors.is_empty() {
+            return;
+        }
+        self.config_errors_reported = true;
+        for err in &self.config.errors {
+            print_to_pipe(pipe_message, &format!("config error: {err}\n"));
+        }
+    }
+
+    fn queue_command(&mut self, command: Command, pipe_message: PipeMessage) {
+        let timeout = self.config.queue_timeout;
+        if let PipeSource::Cli(pipe_id) = &pipe_message.source {
+            block_cli_pipe_input(pipe_id);
+        }
+        self.queued_commands.push(QueuedCommand {
+            command,
+            pipe_message,
+            deadline: Instant::now() + timeout,
+        });
+        self.scheduler
+            .schedule(DeadlineKind::QueueTimeout, timeout + TIMER_SLACK);
+    }
+
+    fn replay_queued_commands(&mut self) {
+        if !self.state.has_received_updates() {
+            return;
+        }
+        for queued in std::mem::take(&mut self.queued_commands) {
+            let held = self.run_command(queued.command, &queued.pipe_message);
+            if !held {
+                release_pipe(&queued.pipe_message);
+            }
+        }
+    }
+
+    fn reject_queued_commands(&mut self, err: &str) {
+        for queued in std::mem::take(&mut self.queued_commands) {
+            print_to_pipe(&queued.pipe_message, err);
+            release_pipe(&queued.pipe_message);
+        }
+    }
+
+    fn expire_queued_commands(&mut self) {
+        let now = Instant::now();
+        let (expired, queued): (Vec<_>, Vec<_>) = std::mem::take(&mut self.queued_commands)
+            .into_iter()
+            .partition(|queued| queued.deadline <= now);
+        self.queued_commands = queued;
+
+        for queued in expired {
+            print_to_pipe(
+                &queued.pipe_message,
+                "timed out waiting for the plugin to receive PaneUpdate and TabUpdate; check that emotitle has been granted its permissions",
+            );
+            release_pipe(&queued.pipe_message);
+        }
+    }
+
     fn apply_pending_restores(&mut self) {
         let pane_restores = self.state.take_pending_pane_restores();
         for (pane_ref, original_title) in pane_restores {
-            rename_pane(&pane_ref, original_title);
+            if self.state.should_uncover_pane(&pane_ref, &original_title) {
+                rename_pane(&pane_ref, String::new());
+            } else {
+                self.state
+                    .expect_pane_echo(pane_ref.clone(), &original_title);
+                rename_pane(&pane_ref, original_title);
+            }
         }
 
         let tab_restores = self.state.take_pending_tab_restores();
         for (tab_index, original_title) in tab_restores {
-            if let Some(rename_target) = self.state.tab_rename_target(tab_index) {
-                rename_tab(rename_target, original_title);
+            if self
+                .state
+                .should_undo_tab_rename(tab_index, &original_title)
+            {
+                undo_rename_tab();
+            } else if let Some(rename_target) = self.state.tab_rename_target(tab_index) {
+                self.state.expect_tab_echo(tab_index, &original_title);
+                rename_tab(rename_target, original_title.clone());
+                if self
+                    .state
+                    .track_tab_rename(tab_index, rename_target, &original_title)
+                {
+                    self.arm_tab_rename_check();
+                }
+            }
+        }
+
+        self.schedule_restore();
+    }
+
+    fn schedule_restore(&mut self) {
+        let Some(next_at) = self.state.next_restore_at() else {
+            return;
+        };
+        let delay = next_at.saturating_duration_since(Instant::now()) + TIMER_SLACK;
+        if self
+            .scheduler
+            .next_deadline(DeadlineKind::Restore)
+            .is_some_and(|pending| pending <= Instant::now() + delay)
+        {
+            return;
+        }
+        self.scheduler.schedule(DeadlineKind::Restore, delay);
+    }
+
+    fn arm_tab_rename_check(&mut self) {
+        if !self.scheduler.is_scheduled(DeadlineKind::TabRenameCheck) {
+            self.scheduler.schedule(
+                DeadlineKind::TabRenameCheck,
+                TAB_RENAME_CHECK_TIMEOUT + TIMER_SLACK,
+            );
+        }
+    }
+
+    /// Renaming a pane to an empty name hands its title back to the program,
+    /// like `undo_rename_pane` but for that pane only, whatever has the focus.
+    fn uncover_followed_panes(&mut self) {
+        for pane_ref in self.state.take_followed_panes_to_uncover() {
+            rename_pane(&pane_ref, String::new());
+        }
+    }
+
+    /// Schedules the next animation tick unless one is already due by then,
+    /// so a faster animation started after a slow one is not held back.
+    fn arm_animation(&mut self) {
+        let Some(next_at) = self.state.next_animation_at() else {
+            return;
+        };
+        // Never tick faster than the fastest allowed frame, so a backlog of
+        // due frames is spread out instead of renamed all at once.
+        let delay = next_at
+            .saturating_duration_since(Instant::now())
+            .max(MIN_FRAME_INTERVAL);
+        if self
+            .scheduler
+            .next_deadline(DeadlineKind::Animate)
+            .is_some_and(|pending| pending <= Instant::now() + delay)
+        {
+            return;
+        }
+        self.scheduler.schedule(DeadlineKind::Animate, delay);
+    }
+
+    fn advance_animations(&mut self) {
+        let frames = self.state.step_animations(MAX_ANIMATION_RENAMES_PER_TICK);
+        for (pane_ref, title) in frames.panes {
+            self.state.expect_pane_echo(pane_ref.clone(), &title);
+            rename_pane(&pane_ref, title);
+        }
+        for (tab_index, title) in frames.tabs {
+            let Some(rename_target) = self.state.tab_rename_target(tab_index) else {
+                continue;
+            };
+            self.state.expect_tab_echo(tab_index, &title);
+            rename_tab(rename_target, title.clone());
+            if self
+                .state
+                .track_tab_rename(tab_index, rename_target, &title)
+            {
+                self.arm_tab_rename_check();
             }
         }
+        self.arm_animation();
+    }
+
+    fn apply_tab_rename_checks(&mut self) {
+        let renames = self.state.verify_tab_renames();
+        for (rename_target, title) in renames {
+            rename_tab(rename_target, title);
+        }
+        if self.state.has_tab_rename_checks() {
+            self.arm_tab_rename_check();
+        }
+    }
 
-        if self.state.has_pending_tab_restores() {
-            set_timeout(1.0);
+    /// Holds the response of a `wait=true` command until zellij reports the
+    /// new titles back, keeping the CLI pipe blocked in the meantime.
+    fn wait_for_titles(
+        &mut self,
+        pipe_id: &str,
+        results: Vec<(String, Result<Applied, String>)>,
+    ) -> bool {
+        let mut ack = PendingAck::new(pipe_id.to_string(), results);
+        ack.settle(&self.state);
+        if ack.is_settled() {
+            cli_pipe_output(pipe_id, &format_results(&ack.results()));
+            return false;
+        }
+
+        block_cli_pipe_input(pipe_id);
+        self.pending_acks.push(ack);
+        self.scheduler
+            .schedule(DeadlineKind::WaitTimeout, WAIT_TIMEOUT + TIMER_SLACK);
+        true
+    }
+
+    fn settle_pending_acks(&mut self) {
+        let acks = std::mem::take(&mut self.pending_acks);
+        for mut ack in acks {
+            ack.settle(&self.state);
+            if !ack.is_settled() && ack.is_expired() {
+                ack.expire();
+            }
+            if ack.is_settled() {
+                cli_pipe_output(&ack.pipe_id, &format_results(&ack.results()));
+                unblock_cli_pipe_input(&ack.pipe_id);
+            } else {
+                self.pending_acks.push(ack);
+            }
         }
     }
 
@@ -99,83 +403,178 @@ impl PluginState {
         &mut self,
         command: Command,
         pipe_message: &PipeMessage,
-    ) -> Result<(), String> {
-        match command.target {
-            Target::Pane { pane_id } => {
-                let pane_ref = match pane_id {
-                    Some(id) => PaneRef::Terminal(id),
-                    None => self.state.focused_pane_ref().ok_or_else(|| {
-                        "could not resolve focused pane; ensure plugin received PaneUpdate"
-                            .to_string()
-                    })?,
-                };
-                self.apply_pane(
-                    pane_ref,
-                    command.emojis,
-                    command.mode,
-                    command.trace,
-                    pipe_message,
-                )
+    ) -> Vec<(String, Result<Applied, String>)> {
+        let resolved = self.resolve_targets(&command, pipe_message);
+
+        let mut applied: Vec<(ResolvedTarget, Result<Applied, String>)> = Vec::new();
+        let mut results = Vec::new();
+        for (label, target) in resolved {
+            let result = match target {
+                Ok(target) => {
+                    if let Some((_, result)) = applied.iter().find(|(done, _)| *done == target) {
+                        result.clone()
+                    } else {
+                        let result = match &target {
+                            ResolvedTarget::Pane(pane_ref) => {
+                                self.apply_pane(pane_ref.clone(), &command, pipe_message)
+                            }
+                            ResolvedTarget::Tab(tab_index) => {
+                                self.apply_tab(*tab_index, &command, pipe_message)
+                            }
+                        };
+                        applied.push((target, result.clone()));
+                        result
+                    }
+                }
+                Err(err) => Err(err),
+            };
+            results.push((label, result));
+        }
+        results
+    }
+
+    fn resolve_targets(
+        &self,
+        command: &Command,
+        pipe_message: &PipeMessage,
+    ) -> Vec<(String, Result<ResolvedTarget, String>)> {
+        match &command.target {
+            Target::Pane { pane_ids } if pane_ids.is_empty() => {
+                let pane_ref = self
+                    .state
+                    .focused_pane_ref_in_layer(command.floating)
+                    .ok_or_else(|| match command.floating {
+                        Some(true) => "could not resolve focused floating pane".to_string(),
+                        Some(false) => "could not resolve focused tiled pane".to_string(),
+                        None => "could not resolve focused pane; ensure plugin received PaneUpdate"
+                            .to_string(),
+                    });
+                vec![(
+                    "focused pane".to_string(),
+                    pane_ref.map(ResolvedTarget::Pane),
+                )]
             }
-            Target::Tab { pane_id, tab_index } => {
-                let tab_index = if let Some(tab_index) = tab_index {
-                    tab_index
-                } else if let Some(pane_id) = pane_id {
+            Target::Pane { pane_ids } => pane_ids
+                .iter()
+                .map(|id| {
+                    (
+                        format!("pane_id={id}"),
+                        Ok(ResolvedTarget::Pane(PaneRef::Terminal(*id))),
+                    )
+                })
+                .collect(),
+            Target::Tab {
+                pane_ids,
+                tab_indices,
+            } if pane_ids.is_empty() && tab_indices.is_empty() => {
+                let tab_index = self
+                    .state
+                    .focused_tab_index()
+                    .or_else(|| self.state.focused_tab_index_from_manifest())
+                    .or_else(|| {
+                        self.state.focused_pane_ref().and_then(|pane_ref| {
+                            self.state.resolve_tab_index_from_pane_ref(&pane_ref)
+                        })
+                    })
+                    .ok_or_else(|| {
+                        format!(
+                            "could not resolve focused tab; ensure plugin received TabUpdate ({})",
+                            self.state.tab_resolution_debug()
+                        )
+                    });
+                vec![(
+                    "focused tab".to_string(),
+                    tab_index.map(ResolvedTarget::Tab),
+                )]
+            }
+            Target::Tab {
+                pane_ids,
+                tab_indices,
+            } => {
+                let by_index = tab_indices.iter().map(|tab_index| {
+                    (
+                        format!("tab_index={tab_index}"),
+                        Ok(ResolvedTarget::Tab(*tab_index)),
+                    )
+                });
+                let by_pane = pane_ids.iter().map(|pane_id| {
                     if command.trace {
                         print_to_pipe(
                             pipe_message,
                             &format!(
                                 "[trace] {}\n",
-                                self.state.trace_resolution_for_pane(pane_id)
+                                self.state.trace_resolution_for_pane(*pane_id)
                             ),
                         );
                     }
-                    self.state.resolve_tab_index_from_pane_id(pane_id).ok_or_else(|| {
-                        format!(
-                            "could not resolve tab_index from pane_id={pane_id}; ensure plugin received PaneUpdate ({})",
-                            self.state.tab_resolution_debug()
-                        )
-                    })?
-                } else {
-                    self.state
-                        .focused_tab_index()
-                        .or_else(|| self.state.focused_tab_index_from_manifest())
-                        .or_else(|| {
-                            self.state
-                                .focused_pane_ref()
-                                .and_then(|pane_ref| match pane_ref {
-                                    PaneRef::Terminal(id) => {
-                                        self.state.resolve_tab_index_from_pane_id(id)
-                                    }
-                                    PaneRef::Plugin(_) => None,
-                                })
-                        })
+                    let tab_index = self
+                        .state
+                        .resolve_tab_index_from_pane_id(*pane_id)
                         .ok_or_else(|| {
                             format!(
-                                "could not resolve focused tab; ensure plugin received TabUpdate ({})",
+                                "could not resolve tab_index from pane_id={pane_id}; ensure plugin received PaneUpdate ({})",
                                 self.state.tab_resolution_debug()
                             )
-                        })?
-                };
-                self.apply_tab(
-                    tab_index,
-                    command.emojis,
-                    command.mode,
-                    command.trace,
-                    pipe_message,
-                )
+                        });
+                    (
+                        format!("pane_id={pane_id}"),
+                        tab_index.map(ResolvedTarget::Tab),
+                    )
+                });
+                by_index.chain(by_pane).collect()
             }
+            Target::All { kind } => self.broadcast_targets(*kind, command.floating, false),
+            Target::Unfocused { kind } => self.broadcast_targets(*kind, command.floating, true),
         }
     }
 
+    fn broadcast_targets(
+        &self,
+        kind: TargetKind,
+        floating: Option<bool>,
+        skip_focused: bool,
+    ) -> Vec<(String, Result<ResolvedTarget, String>)> {
+        let targets: Vec<ResolvedTarget> = match kind {
+            TargetKind::Pane => {
+                let focused = self.state.focused_pane_ref();
+                self.state
+                    .selectable_pane_refs(floating)
+                    .into_iter()
+                    .filter(|pane_ref| !skip_focused || Some(pane_ref) != focused.as_ref())
+                    .map(ResolvedTarget::Pane)
+                    .collect()
+            }
+            TargetKind::Tab => {
+                let focused = self.state.focused_tab_index();
+                self.state
+                    .tab_indices()
+                    .into_iter()
+                    .filter(|tab_index| !skip_focused || Some(*tab_index) != focused)
+                    .map(ResolvedTarget::Tab)
+                    .collect()
+            }
+        };
+
+        targets
+            .into_iter()
+            .map(|target| (target.label(), Ok(target)))
+            .collect()
+    }
+
     fn apply_pane(
         &mut self,
         pane_ref: PaneRef,
-        emojis: String,
-        mode: Mode,
-        trace: bool,
+        command: &Command,
         pipe_message: &PipeMessage,
-    ) -> Result<(), String> {
+    ) -> Result<Applied, String> {
+        let trace = command.trace;
+        if self.state.is_pane_suppressed(&pane_ref) {
+            return Err(format!(
+                "{} is suppressed and not visible; refusing to decorate it",
+                ResolvedTarget::Pane(pane_ref).label()
+            ));
+        }
+
         let base_title = self
             .state
             .pane_effective_title(&pane_ref)
@@ -195,12 +594,30 @@ impl PluginState {
             );
             print_to_pipe(
                 pipe_message,
-                &format!("[trace] mode={:?} emojis={}\n", mode, emojis),
+                &format!(
+                    "[trace] mode={:?} emojis={}\n",
+                    command.mode, command.emojis
+                ),
             );
         }
 
-        let new_title = title_with_emojis(&base_title, &emojis);
+        let excluded = self.config.exclusive_with(&command.emojis);
+        let (new_title, evicted) =
+            self.state
+                .decorate_pane(&pane_ref, &base_title, &decoration(command, &excluded));
+        self.state.expect_pane_echo(pane_ref.clone(), &new_title);
         rename_pane(&pane_ref, new_title.clone());
+        if self.state.schedule_pane_restore(&pane_ref, &new_title) {
+            self.schedule_restore();
+        }
+        if let Some(frames) = &command.animate {
+            self.state
+                .animate_pane(pane_ref.clone(), animation(command, frames));
+            self.arm_animation();
+        }
+        if command.follow {
+            self.state.follow_pane(pane_ref.clone(), &new_title);
+        }
 
         if trace {
             print_to_pipe(
@@ -209,17 +626,22 @@ impl PluginState {
             );
         }
 
-        Ok(())
+        Ok(Applied {
+            expectation: Expectation::Pane {
+                pane_ref,
+                title: new_title,
+            },
+            evicted,
+        })
     }
 
     fn apply_tab(
         &mut self,
         tab_index: usize,
-        emojis: String,
-        mode: Mode,
-        trace: bool,
+        command: &Command,
         pipe_message: &PipeMessage,
-    ) -> Result<(), String> {
+    ) -> Result<Applied, String> {
+        let trace = command.trace;
         let current_title = self
             .state
             .tab_effective_title(tab_index)
@@ -230,8 +652,6 @@ impl PluginState {
                     self.state.tab_resolution_debug()
                 )
             })?;
-        let original_title = extract_original_title(&current_title);
-        let base_title = title_with_pinned_segments(&original_title, &current_title);
 
         if trace {
             print_to_pipe(
@@ -240,25 +660,50 @@ impl PluginState {
             );
             print_to_pipe(
                 pipe_message,
-                &format!("[trace] mode={:?} emojis={}\n", mode, emojis),
+                &format!(
+                    "[trace] mode={:?} emojis={}\n",
+                    command.mode, command.emojis
+                ),
             );
         }
 
-        let new_title = title_with_emojis(&base_title, &emojis);
-        self.state.clear_pending_tab_restore(tab_index);
+        let tab_id = self.state.tab_id(tab_index).ok_or_else(|| {
+            format!(
+                "could not resolve tab identity for tab_index={tab_index}; ensure plugin received TabUpdate ({})",
+                self.state.tab_resolution_debug()
+            )
+        })?;
         let rename_target = self.state.tab_rename_target(tab_index).ok_or_else(|| {
             format!(
                 "could not resolve tab rename target for tab_index={tab_index}; ensure plugin received TabUpdate ({})",
                 self.state.tab_resolution_debug()
             )
         })?;
+        let excluded = self.config.exclusive_with(&command.emojis);
+        let (new_title, evicted) =
+            self.state
+                .decorate_tab(tab_id, &current_title, &decoration(command, &excluded));
         if trace {
             print_to_pipe(
                 pipe_message,
                 &format!("[trace] Rename: {} {}\n", rename_target, new_title),
             );
         }
+        self.state.expect_tab_echo(tab_index, &new_title);
         rename_tab(rename_target, new_title.clone());
+        if self.state.schedule_tab_restore(tab_index, &new_title) {
+            self.schedule_restore();
+        }
+        if self
+            .state
+            .track_tab_rename(tab_index, rename_target, &new_title)
+        {
+            self.arm_tab_rename_check();
+        }
+        if let Some(frames) = &command.animate {
+            self.state.animate_tab(tab_id, animation(command, frames));
+            self.arm_animation();
+        }
 
         if trace {
             print_to_pipe(
@@ -267,10 +712,35 @@ impl PluginState {
             );
         }
 
-        Ok(())
+        Ok(Applied {
+            expectation: Expectation::Tab {
+                tab_id,
+                title: new_title,
+            },
+            evicted,
+        })
     }
 }
 
+fn decoration<'a>(command: &'a Command, excluded: &'a [String]) -> Decoration<'a> {
+    Decoration {
+        emojis: &command.emojis,
+        mode: command.mode,
+        priority: command.priority,
+        excluded,
+        slot: command.slot.as_deref(),
+    }
+}
+
+fn animation(command: &Command, frames: &[String]) -> Animation {
+    Animation::new(
+        frames.to_vec(),
+        command.mode == Mode::Permanent,
+        command.interval,
+        command.slot.clone(),
+    )
+}
+
 fn rename_pane(pane_ref: &PaneRef, title: String) {
     match pane_ref {
         PaneRef::Terminal(id) => rename_terminal_pane(*id, title),
@@ -278,6 +748,43 @@ fn rename_pane(pane_ref: &PaneRef, title: String) {
     }
 }
 
+/// A single target keeps the historical bare `ok` / error response; several
+/// targets are reported one line each, prefixed with how they were addressed.
+fn format_results(results: &[(String, Result<String, String>)]) -> String {
+    if let [(_, result)] = results {
+        return match result {
+            Ok(summary) => summary.clone(),
+            Err(err) => err.clone(),
+        };
+    }
+
+    results
+        .iter()
+        .map(|(label, result)| match result {
+            Ok(summary) => format!("{label}: {summary}"),
+            Err(err) => format!("{label}: {err}"),
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn missing_permissions_error() -> String {
+    let permissions = REQUIRED_PERMISSIONS
+        .iter()
+        .map(|permission| format!("{permission:?}"))
+        .collect::<Vec<_>>()
+        .join(", ");
+    format!(
+        "emotitle was denied the permissions it needs ({permissions}); grant them and reload the plugin"
+    )
+}
+
+fn release_pipe(pipe_message: &PipeMessage) {
+    if let PipeSource::Cli(pipe_id) = &pipe_message.source {
+        unblock_cli_pipe_input(pipe_id);
+    }
+}
+
 fn print_to_pipe(pipe_message: &PipeMessage, message: &str) {
     let pipe_id = match &pipe_message.source {
         PipeSource::Cli(id) => id.clone(),
@@ -0,0 +1,136 @@
+use std::time::{Duration, Instant};
+
+use crate::state::{EmotitleState, PaneRef};
+
+pub const WAIT_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// A title we renamed a target to and want to see reported back by zellij.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Expectation {
+    Pane { pane_ref: PaneRef, title: String },
+    Tab { tab_id: usize, title: String },
+}
+
+/// What applying a command to one target did.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Applied {
+    pub expectation: Expectation,
+    /// Segments dropped to stay within `max_segments`.
+    pub evicted: Vec<String>,
+}
+
+impl Applied {
+    /// The success line for this target, e.g. `ok (evicted: 🔔, 🚀)`.
+    pub fn summary(&self) -> String {
+        if self.evicted.is_empty() {
+            "ok".to_string()
+        } else {
+            format!("ok (evicted: {})", self.evicted.join(", "))
+        }
+    }
+}
+
+/// The title a target is still awaited with (if any) and its success line.
+type Waiting = (Option<Expectation>, String);
+
+/// Response of a `wait=true` command, held back (with the CLI pipe blocked)
+/// until every target shows its new title or the deadline passes.
+#[derive(Debug)]
+pub struct PendingAck {
+    pub pipe_id: String,
+    results: Vec<(String, Result<Waiting, String>)>,
+    deadline: Instant,
+}
+
+impl PendingAck {
+    pub fn new(pipe_id: String, results: Vec<(String, Result<Applied, String>)>) -> Self {
+        Self {
+            pipe_id,
+            results: results
+                .into_iter()
+                .map(|(label, result)| {
+                    let result = result.map(|applied| {
+                        let summary = applied.summary();
+                        (Some(applied.expectation), summary)
+                    });
+                    (label, result)
+                })
+                .collect(),
+            deadline: Instant::now() + WAIT_TIMEOUT,
+        }
+    }
+
+    /// Marks targets whose title landed (or which disappeared) as settled.
+    pub fn settle(&mut self, state: &EmotitleState) {
+        for (_, result) in &mut self.results {
+            let Ok((waiting, _)) = result else {
+                continue;
+            };
+            let Some(expectation) = waiting else {
+                continue;
+            };
+            match observed_title(state, expectation) {
+                Ok(Some(title)) if title == expected_title(expectation) => *waiting = None,
+                Ok(_) => {}
+                Err(err) => *result = Err(err),
+            }
+        }
+    }
+
+    pub fn is_settled(&self) -> bool {
+        self.results
+            .iter()
+            .all(|(_, result)| !matches!(result, Ok((Some(_), _))))
+    }
+
+    pub fn is_expired(&self) -> bool {
+        Instant::now() >= self.deadline
+    }
+
+    /// Turns every target still waiting into a timeout error.
+    pub fn expire(&mut self) {
+        for (_, result) in &mut self.results {
+            if let Ok((Some(expectation), _)) = result {
+                *result = Err(format!(
+                    "timed out after {}s waiting for title `{}`",
+                    WAIT_TIMEOUT.as_secs(),
+                    expected_title(expectation)
+                ));
+            }
+        }
+    }
+
+    pub fn results(&self) -> Vec<(String, Result<String, String>)> {
+        self.results
+            .iter()
+            .map(|(label, result)| {
+                let result = result.as_ref().map(|(_, summary)| summary.clone());
+                (label.clone(), result.map_err(Clone::clone))
+            })
+            .collect()
+    }
+}
+
+fn expected_title(expectation: &Expectation) -> &str {
+    match expectation {
+        Expectation::Pane { title, .. } | Expectation::Tab { title, .. } => title,
+    }
+}
+
+fn observed_title(
+    state: &EmotitleState,
+    expectation: &Expectation,
+) -> Result<Option<String>, String> {
+    match expectation {
+        Expectation::Pane { pane_ref, .. } => state
+            .pane_title(pane_ref)
+            .map(Some)
+            .ok_or_else(|| "pane was closed before the new title appeared".to_string()),
+        Expectation::Tab { tab_id, .. } => {
+            if state.is_tab_removed(*tab_id) {
+                return Err("tab was closed before the new title appeared".to_string());
+            }
+            Ok(state.tab_title_by_id(*tab_id))
+        }
+    }
+}
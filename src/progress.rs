@@ -0,0 +1,47 @@
+/// How `progress=` is drawn.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProgressStyle {
+    /// `🌑🌒🌓🌔🌕`, one moon.
+    Moon,
+    /// `▰▰▱▱`, four cells.
+    Bar,
+    /// `▁▂▃▄▅▆▇█`, one block.
+    Blocks,
+}
+
+const MOONS: &[&str] = &["🌑", "🌒", "🌓", "🌔", "🌕"];
+const BLOCKS: &[&str] = &["▁", "▂", "▃", "▄", "▅", "▆", "▇", "█"];
+const BAR_CELLS: usize = 4;
+
+impl ProgressStyle {
+    pub fn parse(value: &str) -> Result<Self, String> {
+        match value.trim() {
+            "moon" => Ok(Self::Moon),
+            "bar" => Ok(Self::Bar),
+            "blocks" => Ok(Self::Blocks),
+            other => Err(format!("style must be moon, bar or blocks, got {other:?}")),
+        }
+    }
+
+    /// Renders `percent` (0..=100), rounding to the nearest step.
+    pub fn render(self, percent: u8) -> String {
+        let step = |steps: usize| (usize::from(percent) * steps + 50) / 100;
+        match self {
+            Self::Moon => MOONS[step(MOONS.len() - 1)].to_string(),
+            Self::Blocks => BLOCKS[step(BLOCKS.len() - 1)].to_string(),
+            Self::Bar => {
+                let filled = step(BAR_CELLS);
+                format!("{}{}", "▰".repeat(filled), "▱".repeat(BAR_CELLS - filled))
+            }
+        }
+    }
+}
+
+pub fn parse_percent(value: &str) -> Result<u8, String> {
+    value
+        .trim()
+        .parse::<u8>()
+        .ok()
+        .filter(|percent| *percent <= 100)
+        .ok_or_else(|| format!("progress must be an integer from 0 to 100, got {value:?}"))
+}
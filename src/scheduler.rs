@@ -0,0 +1,87 @@
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+use std::time::{Duration, Instant};
+
+use zellij_tile::prelude::set_timeout;
+
+/// How far from the armed deadline a timer may fire and still be the armed
+/// one. Also added to deadlines that are re-checked against their own
+/// `Instant`, so the timer never lands just before the check considers them
+/// overdue.
+pub const TIMER_SLACK: Duration = Duration::from_millis(100);
+
+/// What a deadline is for; the plugin runs the matching work once it is due.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum DeadlineKind {
+    Restore,
+    TabRenameCheck,
+    WaitTimeout,
+    QueueTimeout,
+    Animate,
+}
+
+/// Priority queue of every timed behaviour in the plugin. zellij timers
+/// cannot be cancelled, so only the nearest deadline has a timer armed;
+/// later ones are armed in turn as the earlier ones fire.
+#[derive(Debug, Default)]
+pub struct Scheduler {
+    deadlines: BinaryHeap<Reverse<(Instant, DeadlineKind)>>,
+    armed: Option<Instant>,
+}
+
+impl Scheduler {
+    pub fn schedule(&mut self, kind: DeadlineKind, delay: Duration) {
+        self.deadlines.push(Reverse((Instant::now() + delay, kind)));
+        self.arm();
+    }
+
+    pub fn is_scheduled(&self, kind: DeadlineKind) -> bool {
+        self.next_deadline(kind).is_some()
+    }
+
+    /// The earliest pending deadline of `kind`.
+    pub fn next_deadline(&self, kind: DeadlineKind) -> Option<Instant> {
+        self.deadlines
+            .iter()
+            .filter(|Reverse((_, scheduled))| *scheduled == kind)
+            .map(|Reverse((deadline, _))| *deadline)
+            .min()
+    }
+
+    /// Pops every deadline that has passed, once per kind, and arms the timer
+    /// for the next one. Call on each `Event::Timer`.
+    pub fn take_due(&mut self) -> Vec<DeadlineKind> {
+        let now = Instant::now();
+        // The armed timer may fire a hair before its deadline by our clock.
+        // A firing well before it is left over from an earlier arm: the armed
+        // timer is still on its way, so it must not be armed again.
+        if self.armed.is_some_and(|armed| armed <= now + TIMER_SLACK) {
+            self.armed = None;
+        }
+
+        let mut due = Vec::new();
+        while let Some(Reverse((deadline, kind))) = self.deadlines.peek().copied() {
+            if deadline > now {
+                break;
+            }
+            self.deadlines.pop();
+            if !due.contains(&kind) {
+                due.push(kind);
+            }
+        }
+
+        self.arm();
+        due
+    }
+
+    fn arm(&mut self) {
+        let Some(Reverse((next, _))) = self.deadlines.peek().copied() else {
+            return;
+        };
+        if self.armed.is_some_and(|armed| armed <= next) {
+            return;
+        }
+        self.armed = Some(next);
+        set_timeout(next.saturating_duration_since(Instant::now()).as_secs_f64());
+    }
+}
@@ -1,15 +1,28 @@
+use std::cmp::Reverse;
 use std::collections::{HashMap, HashSet};
+use std::time::{Duration, Instant};
 
 use serde::Serialize;
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
 use zellij_tile::prelude::{PaneInfo, PaneManifest, TabInfo};
 
-use crate::tab_index_tracker::{InternalIndexEntry, TabIndexEvent, TabIndexTracker};
+use crate::animation::Animation;
+use crate::command::{Mode, Priority};
+use crate::tab_index_tracker::{InternalIndexEntry, PaneKey, TabIndexEvent, TabIndexTracker};
+
+/// How long a tab rename may take to show up in a `TabUpdate`.
+pub const TAB_RENAME_CHECK_TIMEOUT: Duration = Duration::from_secs(1);
+const MAX_TAB_RENAME_ATTEMPTS: usize = 8;
+const ECHO_TIMEOUT: Duration = Duration::from_secs(2);
 
 #[derive(Serialize)]
 pub struct PaneDebugInfo {
     pub id: u32,
     pub is_plugin: bool,
     pub is_focused: bool,
+    pub is_floating: bool,
+    pub is_suppressed: bool,
     pub title: String,
 }
 
@@ -23,6 +36,8 @@ pub struct TabDebugInfo {
 
 #[derive(Serialize)]
 pub struct InfoDebug {
+    pub permission: PermissionState,
+    pub config_errors: Vec<String>,
     pub tabs: Vec<TabDebugInfo>,
     pub focused_tab_index: Option<usize>,
     pub focused_pane: Option<String>,
@@ -30,6 +45,15 @@ pub struct InfoDebug {
     pub event_history: Vec<TabIndexEvent>,
 }
 
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PermissionState {
+    #[default]
+    Pending,
+    Granted,
+    Denied,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum PaneRef {
     Terminal(u32),
@@ -37,18 +61,136 @@ pub enum PaneRef {
 }
 
 #[derive(Debug, Clone)]
-struct PendingTabRestore {
+struct PaneTitleOrigin {
+    title: String,
+    dynamic: bool,
+}
+
+#[derive(Debug, Clone)]
+struct ExpectedEcho {
+    title: String,
+    issued_at: Instant,
+}
+
+impl ExpectedEcho {
+    fn new(title: &str) -> Self {
+        Self {
+            title: title.to_string(),
+            issued_at: Instant::now(),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct FollowedPane {
+    base: String,
+    decorations: String,
+    /// Renamed to an empty name; the next `PaneUpdate` shows the program title.
+    awaiting_base: bool,
+}
+
+#[derive(Debug, Clone)]
+struct TabRenameCheck {
+    pane_keys: Vec<PaneKey>,
+    rename_target: u32,
     title: String,
-    anchor_pane_id: Option<u32>,
+    previous_names: Vec<(Vec<PaneKey>, String)>,
+    attempts: usize,
+    issued_at: Instant,
 }
 
 #[derive(Default)]
 pub struct EmotitleState {
     pub pane_manifest: Option<PaneManifest>,
     pub tab_infos: Vec<TabInfo>,
-    pending_pane_restores: HashMap<PaneRef, String>,
-    pending_tab_restores: HashMap<usize, PendingTabRestore>,
+    pub permission: PermissionState,
+    pub pane_format: TitleFormat,
+    pub tab_format: TitleFormat,
+    pub config_errors: Vec<String>,
+    pub restore_delay: Duration,
+    pub min_dwell: Duration,
+    panes: HashMap<PaneRef, PaneState>,
+    /// Keyed by the tracker's `tab_id`.
+    tabs: HashMap<usize, TabState>,
     tab_index_tracker: TabIndexTracker,
+    tab_rename_checks: Vec<TabRenameCheck>,
+    pane_focus_since: Option<(PaneRef, Instant)>,
+    tab_focus_since: Option<(usize, Instant)>,
+}
+
+#[derive(Default)]
+struct PaneState {
+    pending_restore: Option<PendingRestore>,
+    echo: Option<ExpectedEcho>,
+    /// The original title before `max_width` shortened it.
+    full_title: Option<String>,
+    repeats: HashMap<String, usize>,
+    segments: Vec<Segment>,
+    animation: Option<Animation>,
+    title_origin: Option<PaneTitleOrigin>,
+    followed: Option<FollowedPane>,
+    to_uncover: bool,
+}
+
+struct PendingRestore {
+    title: String,
+    due: Instant,
+}
+
+#[derive(Default)]
+struct TabState {
+    pending_restore: Option<PendingRestore>,
+    echo: Option<ExpectedEcho>,
+    full_title: Option<String>,
+    repeats: HashMap<String, usize>,
+    segments: Vec<Segment>,
+    animation: Option<Animation>,
+}
+
+impl PaneState {
+    fn restored(&mut self) {
+        self.repeats.clear();
+        self.segments.retain(Segment::is_pinned);
+    }
+}
+
+impl TabState {
+    fn restored(&mut self) {
+        self.repeats.clear();
+        self.segments.retain(Segment::is_pinned);
+    }
+}
+
+#[derive(Default)]
+pub struct AnimationFrames {
+    pub panes: Vec<(PaneRef, String)>,
+    pub tabs: Vec<(usize, String)>,
+}
+
+enum Animated {
+    Pane(PaneRef),
+    Tab(usize),
+}
+
+pub struct Decoration<'a> {
+    pub emojis: &'a str,
+    pub mode: Mode,
+    pub priority: Priority,
+    pub excluded: &'a [String],
+    pub slot: Option<&'a str>,
+}
+
+#[derive(Debug, Clone)]
+struct Segment {
+    text: String,
+    priority: Priority,
+    slot: Option<String>,
+}
+
+impl Segment {
+    fn is_pinned(&self) -> bool {
+        self.text.starts_with('📌')
+    }
 }
 
 impl EmotitleState {
@@ -60,34 +202,35 @@ impl EmotitleState {
             .map(pane_ref_from_pane_info)
             .collect();
 
-        self.pending_pane_restores
+        self.panes
             .retain(|pane_ref, _| current_panes.contains(pane_ref));
-
-        self.remap_tab_state_with_manifest(&pane_manifest);
+        let echoed = self.take_pane_echoes(&pane_manifest);
+        self.observe_pane_titles(&pane_manifest, &echoed);
 
         self.pane_manifest = Some(pane_manifest);
         let tab_panes = self.build_tab_panes();
         self.tab_index_tracker
             .update_for_pane_update(&self.tab_infos, &tab_panes);
         let pane_manifest = self.pane_manifest.clone().unwrap();
-        self.clean_focused_panes_on_focus(&pane_manifest)
+        self.track_focus();
+        self.clean_focused_panes_on_focus(&pane_manifest, &echoed)
     }
 
     pub fn update_tab_infos(&mut self, tab_infos: Vec<TabInfo>) -> bool {
-        let current_tabs: HashSet<usize> = tab_infos.iter().map(|tab| tab.position).collect();
-
-        self.pending_tab_restores
-            .retain(|tab_index, _| current_tabs.contains(tab_index));
-
         self.tab_infos = tab_infos;
         let tab_panes = self.build_tab_panes();
         self.tab_index_tracker
             .update_for_tab_update(&self.tab_infos, &tab_panes);
+        let tracker = &self.tab_index_tracker;
+        self.tabs
+            .retain(|tab_id, _| !tracker.is_tab_removed(*tab_id));
         let tab_infos = self.tab_infos.clone();
-        self.clean_focused_tabs_on_focus(&tab_infos)
+        self.track_focus();
+        let echoed = self.take_tab_echoes(&tab_infos);
+        self.clean_focused_tabs_on_focus(&tab_infos, &echoed)
     }
 
-    fn build_tab_panes(&self) -> HashMap<usize, Vec<crate::tab_index_tracker::PaneKey>> {
+    fn build_tab_panes(&self) -> HashMap<usize, Vec<PaneKey>> {
         let manifest = match &self.pane_manifest {
             Some(m) => m,
             None => return HashMap::new(),
@@ -107,21 +250,28 @@ impl EmotitleState {
             let Some(&tab_position) = manifest_to_tab.get(manifest_tab_position) else {
                 continue;
             };
-            let mut keys: Vec<crate::tab_index_tracker::PaneKey> = panes
+            let mut keys: Vec<PaneKey> = panes
                 .iter()
-                .filter(|pane| !pane.is_plugin)
-                .map(|pane| crate::tab_index_tracker::PaneKey {
-                    is_plugin: false,
+                .map(|pane| PaneKey {
+                    is_plugin: pane.is_plugin,
                     id: pane.id,
                 })
                 .collect();
-            keys.sort_by_key(|key| key.id);
+            keys.sort_by_key(|key| (key.is_plugin, key.id));
             result.insert(tab_position, keys);
         }
         result
     }
 
+    pub fn has_received_updates(&self) -> bool {
+        self.pane_manifest.is_some() && !self.tab_infos.is_empty()
+    }
+
     pub fn resolve_tab_index_from_pane_id(&self, pane_id: u32) -> Option<usize> {
+        self.resolve_tab_index_from_pane_ref(&PaneRef::Terminal(pane_id))
+    }
+
+    pub fn resolve_tab_index_from_pane_ref(&self, pane_ref: &PaneRef) -> Option<usize> {
         let manifest = self.pane_manifest.as_ref()?;
         let candidates: Vec<usize> = manifest
             .panes
@@ -129,7 +279,7 @@ impl EmotitleState {
             .filter_map(|(tab_position, panes)| {
                 panes
                     .iter()
-                    .any(|pane| !pane.is_plugin && pane.id == pane_id)
+                    .any(|pane| pane_matches(pane, pane_ref))
                     .then_some(*tab_position)
             })
             .filter_map(|manifest_tab_position| {
@@ -163,7 +313,7 @@ impl EmotitleState {
             .find_map(|(tab_position, panes)| {
                 panes
                     .iter()
-                    .any(|pane| !pane.is_plugin && pane.id == pane_id && pane.is_focused)
+                    .any(|pane| pane_matches(pane, pane_ref) && pane.is_focused)
                     .then_some(*tab_position)
             })
             .and_then(|manifest_tab_position| {
@@ -173,16 +323,86 @@ impl EmotitleState {
     }
 
     pub fn focused_pane_ref(&self) -> Option<PaneRef> {
-        self.pane_manifest.as_ref().and_then(|manifest| {
+        self.focused_pane_ref_in_layer(None)
+    }
+
+    pub fn focused_pane_ref_in_layer(&self, floating: Option<bool>) -> Option<PaneRef> {
+        let manifest = self.pane_manifest.as_ref()?;
+        let active_manifest_position = self
+            .focused_tab_index()
+            .and_then(|tab_index| self.manifest_tab_position_for_tab_position(tab_index));
+
+        let Some(manifest_position) = active_manifest_position else {
+            return manifest
+                .panes
+                .values()
+                .flat_map(|panes| panes.iter())
+                .filter(|pane| pane.is_focused && !pane.is_suppressed)
+                .find(|pane| floating.is_none_or(|floating| pane.is_floating == floating))
+                .map(pane_ref_from_pane_info);
+        };
+
+        let panes = manifest.panes.get(&manifest_position)?;
+        let floating = floating.unwrap_or_else(|| {
+            self.floating_panes_visible(manifest_position)
+                .unwrap_or(false)
+        });
+        panes
+            .iter()
+            .find(|pane| pane.is_focused && !pane.is_suppressed && pane.is_floating == floating)
+            .map(pane_ref_from_pane_info)
+    }
+
+    pub fn is_pane_suppressed(&self, pane_ref: &PaneRef) -> bool {
+        self.pane_manifest.as_ref().is_some_and(|manifest| {
             manifest
                 .panes
                 .values()
                 .flat_map(|panes| panes.iter())
-                .find(|pane| pane.is_focused)
-                .map(pane_ref_from_pane_info)
+                .any(|pane| pane_matches(pane, pane_ref) && pane.is_suppressed)
         })
     }
 
+    fn floating_panes_visible(&self, manifest_tab_position: usize) -> Option<bool> {
+        let tab_position = self.tab_position_for_manifest_position(manifest_tab_position)?;
+        self.tab_infos
+            .iter()
+            .find(|tab| tab.position == tab_position)
+            .map(|tab| tab.are_floating_panes_visible)
+    }
+
+    fn pane_has_visible_focus(&self, manifest_tab_position: usize, pane: &PaneInfo) -> bool {
+        if !pane.is_focused || pane.is_suppressed {
+            return false;
+        }
+        match self.floating_panes_visible(manifest_tab_position) {
+            Some(floating_visible) => pane.is_floating == floating_visible,
+            None => true,
+        }
+    }
+
+    pub fn selectable_pane_refs(&self, floating: Option<bool>) -> Vec<PaneRef> {
+        let Some(manifest) = self.pane_manifest.as_ref() else {
+            return Vec::new();
+        };
+        let mut positions: Vec<usize> = manifest.panes.keys().copied().collect();
+        positions.sort_unstable();
+        positions
+            .iter()
+            .filter_map(|position| manifest.panes.get(position))
+            .flat_map(|panes| panes.iter())
+            .filter(|pane| pane.is_selectable && !pane.is_suppressed)
+            .filter(|pane| floating.is_none_or(|floating| pane.is_floating == floating))
+            .map(pane_ref_from_pane_info)
+            .collect()
+    }
+
+    pub fn tab_indices(&self) -> Vec<usize> {
+        let mut positions: Vec<usize> = self.tab_infos.iter().map(|tab| tab.position).collect();
+        positions.sort_unstable();
+        positions
+    }
+
     pub fn focused_tab_index(&self) -> Option<usize> {
         self.tab_infos
             .iter()
@@ -300,6 +520,8 @@ impl EmotitleState {
                                 id: p.id,
                                 is_plugin: p.is_plugin,
                                 is_focused: p.is_focused,
+                                is_floating: p.is_floating,
+                                is_suppressed: p.is_suppressed,
                                 title: p.title.clone(),
                             })
                             .collect()
@@ -321,6 +543,8 @@ impl EmotitleState {
         let event_history = self.tab_index_tracker.get_event_history();
 
         let info = InfoDebug {
+            permission: self.permission,
+            config_errors: self.config_errors.clone(),
             tabs: tab_debug_infos,
             focused_tab_index: self.focused_tab_index(),
             focused_pane: self.focused_pane_ref().map(|p| format!("{:?}", p)),
@@ -332,18 +556,16 @@ impl EmotitleState {
     }
 
     pub fn pane_title(&self, pane_ref: &PaneRef) -> Option<String> {
-        self.pane_manifest.as_ref().and_then(|manifest| {
-            manifest
-                .panes
-                .values()
-                .flat_map(|panes| panes.iter())
-                .find(|pane| pane_matches(pane, pane_ref))
-                .map(|pane| pane.title.clone())
-        })
+        pane_title_in(self.pane_manifest.as_ref()?, pane_ref)
     }
 
     pub fn pane_effective_title(&self, pane_ref: &PaneRef) -> Option<String> {
-        self.pane_title(pane_ref)
+        self.panes
+            .get(pane_ref)
+            .and_then(|pane| pane.echo.as_ref())
+            .filter(|echo| echo.issued_at.elapsed() < ECHO_TIMEOUT)
+            .map(|echo| echo.title.clone())
+            .or_else(|| self.pane_title(pane_ref))
     }
 
     pub fn tab_title(&self, tab_index: usize) -> Option<String> {
@@ -354,7 +576,12 @@ impl EmotitleState {
     }
 
     pub fn tab_effective_title(&self, tab_index: usize) -> Option<String> {
-        self.tab_title(tab_index)
+        self.tab_id(tab_index)
+            .and_then(|tab_id| self.tabs.get(&tab_id))
+            .and_then(|tab| tab.echo.as_ref())
+            .filter(|echo| echo.issued_at.elapsed() < ECHO_TIMEOUT)
+            .map(|echo| echo.title.clone())
+            .or_else(|| self.tab_title(tab_index))
     }
 
     pub fn tab_rename_target(&self, tab_index: usize) -> Option<u32> {
@@ -363,155 +590,746 @@ impl EmotitleState {
             .get_rename_target(&tab_panes, tab_index)
     }
 
-    pub fn tab_anchor_pane_id(&self, tab_index: usize) -> Option<u32> {
-        let manifest = self.pane_manifest.as_ref()?;
-        let manifest_tab_position = self.manifest_tab_position_for_tab_position(tab_index)?;
-        let panes = manifest.panes.get(&manifest_tab_position)?;
-        panes
-            .iter()
-            .filter(|pane| !pane.is_plugin)
-            .min_by_key(|pane| pane.id)
-            .map(|pane| pane.id)
+    pub fn tab_id(&self, tab_index: usize) -> Option<usize> {
+        let tab_panes = self.build_tab_panes();
+        self.tab_index_tracker.tab_id(&tab_panes, tab_index)
     }
 
-    fn clean_focused_panes_on_focus(&mut self, pane_manifest: &PaneManifest) -> bool {
-        let mut set_timer = false;
+    pub fn tab_title_by_id(&self, tab_id: usize) -> Option<String> {
+        let tab_panes = self.build_tab_panes();
+        let tab_index = self.tab_index_tracker.tab_position(&tab_panes, tab_id)?;
+        self.tab_title(tab_index)
+    }
 
-        for pane in pane_manifest.panes.values().flat_map(|panes| panes.iter()) {
-            if !pane.is_focused {
+    pub fn is_tab_removed(&self, tab_id: usize) -> bool {
+        self.tab_index_tracker.is_tab_removed(tab_id)
+    }
+
+    pub fn track_tab_rename(&mut self, tab_index: usize, rename_target: u32, title: &str) -> bool {
+        let tab_panes = self.build_tab_panes();
+        let Some(pane_keys) = tab_panes.get(&tab_index) else {
+            return false;
+        };
+        if self.tab_title(tab_index).as_deref() == Some(title) {
+            return false;
+        }
+
+        self.tab_rename_checks
+            .retain(|check| !keys_overlap(&check.pane_keys, pane_keys));
+        self.tab_rename_checks.push(TabRenameCheck {
+            pane_keys: pane_keys.clone(),
+            rename_target,
+            title: title.to_string(),
+            previous_names: self.tab_names_by_keys(&tab_panes),
+            attempts: 1,
+            issued_at: Instant::now(),
+        });
+        true
+    }
+
+    pub fn has_tab_rename_checks(&self) -> bool {
+        !self.tab_rename_checks.is_empty()
+    }
+
+    pub fn verify_tab_renames(&mut self) -> Vec<(u32, String)> {
+        let checks = std::mem::take(&mut self.tab_rename_checks);
+        let tab_panes = self.build_tab_panes();
+        let mut renames = Vec::new();
+
+        for (check_index, check) in checks.iter().enumerate() {
+            let check = check.clone();
+            let Some(tab_index) = tab_position_for_keys(&tab_panes, &check.pane_keys) else {
+                continue;
+            };
+
+            if self.tab_title(tab_index).as_deref() == Some(check.title.as_str()) {
+                self.tab_index_tracker.mark_verified(&check.pane_keys);
                 continue;
             }
 
-            let pane_ref = pane_ref_from_pane_info(pane);
-            let original_title = extract_original_title(&pane.title);
-            let cleaned_title = title_with_pinned_segments(&original_title, &pane.title);
+            let hijacked = tab_panes.iter().find_map(|(position, keys)| {
+                if keys_overlap(keys, &check.pane_keys) {
+                    return None;
+                }
+                // Another tab waiting for the same title got it through its
+                // own rename, e.g. two tabs with the same name decorated at once.
+                let expects_same_title = checks.iter().enumerate().any(|(other_index, other)| {
+                    other_index != check_index
+                        && other.title == check.title
+                        && keys_overlap(&other.pane_keys, keys)
+                });
+                if expects_same_title {
+                    return None;
+                }
+                let name = self.tab_title(*position)?;
+                let previous = check
+                    .previous_names
+                    .iter()
+                    .find(|(previous_keys, _)| keys_overlap(previous_keys, keys))
+                    .map(|(_, previous)| previous.clone())?;
+                (name == check.title && previous != check.title).then(|| (keys.clone(), previous))
+            });
 
-            if cleaned_title != pane.title {
-                self.pending_pane_restores.insert(pane_ref, cleaned_title);
-                set_timer = true;
+            let previous_name = check
+                .previous_names
+                .iter()
+                .find(|(previous_keys, _)| keys_overlap(previous_keys, &check.pane_keys))
+                .map(|(_, previous)| previous.clone());
+
+            if let Some((hijacked_keys, previous)) = hijacked {
+                renames.push((check.rename_target, previous));
+                self.tab_index_tracker
+                    .record_rename_anomaly(&check.pane_keys, check.rename_target);
+                self.tab_index_tracker
+                    .calibrate(&hijacked_keys, check.rename_target as usize - 1);
+                self.retry_tab_rename(check, tab_index, &tab_panes, &mut renames);
+            } else if previous_name.is_some() && self.tab_title(tab_index) != previous_name {
+                // Renamed to something else in the meantime (by the user or
+                // a newer rename of ours); this check no longer tells anything.
+                continue;
+            } else if check.issued_at.elapsed() < TAB_RENAME_CHECK_TIMEOUT {
+                self.tab_rename_checks.push(check);
+            } else if self.tab_index_tracker.is_verified(&check.pane_keys) {
+                // A verified index that has not answered yet is more likely
+                // slow than wrong: send the rename again and keep watching.
+                if check.attempts < MAX_TAB_RENAME_ATTEMPTS {
+                    renames.push((check.rename_target, check.title.clone()));
+                    self.tab_rename_checks.push(TabRenameCheck {
+                        attempts: check.attempts + 1,
+                        issued_at: Instant::now(),
+                        ..check
+                    });
+                }
+            } else {
+                self.tab_index_tracker
+                    .record_rename_anomaly(&check.pane_keys, check.rename_target);
+                self.tab_index_tracker.skip_missing_index(&check.pane_keys);
+                self.retry_tab_rename(check, tab_index, &tab_panes, &mut renames);
             }
         }
 
-        set_timer
+        renames
     }
 
-    fn clean_focused_tabs_on_focus(&mut self, tab_infos: &[TabInfo]) -> bool {
-        let mut set_timer = false;
+    fn retry_tab_rename(
+        &mut self,
+        check: TabRenameCheck,
+        tab_index: usize,
+        tab_panes: &HashMap<usize, Vec<PaneKey>>,
+        renames: &mut Vec<(u32, String)>,
+    ) {
+        if check.attempts >= MAX_TAB_RENAME_ATTEMPTS {
+            return;
+        }
+        let Some(rename_target) = self
+            .tab_index_tracker
+            .get_rename_target(tab_panes, tab_index)
+        else {
+            return;
+        };
+        if rename_target == check.rename_target {
+            return;
+        }
+
+        renames.push((rename_target, check.title.clone()));
+        self.tab_rename_checks.push(TabRenameCheck {
+            rename_target,
+            attempts: check.attempts + 1,
+            issued_at: Instant::now(),
+            ..check
+        });
+    }
 
+    fn tab_names_by_keys(
+        &self,
+        tab_panes: &HashMap<usize, Vec<PaneKey>>,
+    ) -> Vec<(Vec<PaneKey>, String)> {
+        tab_panes
+            .iter()
+            .filter_map(|(position, keys)| Some((keys.clone(), self.tab_title(*position)?)))
+            .collect()
+    }
+
+    fn pane_original(&self, pane_ref: &PaneRef, title: &str) -> String {
+        let full = self
+            .panes
+            .get(pane_ref)
+            .and_then(|pane| pane.full_title.as_ref());
+        self.pane_format
+            .expand(self.pane_format.original(title, full), full)
+    }
+
+    fn tab_original(&self, tab_index: usize, title: &str) -> String {
+        let full = self
+            .tab_id(tab_index)
+            .and_then(|tab_id| self.tabs.get(&tab_id))
+            .and_then(|tab| tab.full_title.as_ref());
+        self.tab_format
+            .expand(self.tab_format.original(title, full), full)
+    }
+
+    pub fn decorate_pane(
+        &mut self,
+        pane_ref: &PaneRef,
+        title: &str,
+        decoration: &Decoration,
+    ) -> (String, Vec<String>) {
+        let original = self.pane_original(pane_ref, title);
+        let pane = self.panes.entry(pane_ref.clone()).or_default();
+        let segment = match decoration.mode {
+            Mode::Temp => {
+                let shown = self
+                    .pane_format
+                    .contains_segment(title, &original, decoration.emojis);
+                count_repeat(&mut pane.repeats, shown, decoration.emojis)
+            }
+            Mode::Permanent => decoration.emojis.to_string(),
+        };
+        pane.segments = reconcile(
+            &pane.segments,
+            &self.pane_format.segments(title, Some(&original)),
+        );
+        let rendered = render_decoration(
+            &self.pane_format,
+            &mut pane.segments,
+            &original,
+            &segment,
+            decoration,
+        );
+        pane.full_title = Some(original);
+        rendered
+    }
+
+    pub fn decorate_tab(
+        &mut self,
+        tab_id: usize,
+        title: &str,
+        decoration: &Decoration,
+    ) -> (String, Vec<String>) {
+        let tab = self.tabs.entry(tab_id).or_default();
+        let full = tab.full_title.as_ref();
+        let original = self
+            .tab_format
+            .expand(self.tab_format.original(title, full), full);
+        let segment = match decoration.mode {
+            Mode::Temp => {
+                let shown = self
+                    .tab_format
+                    .contains_segment(title, &original, decoration.emojis);
+                count_repeat(&mut tab.repeats, shown, decoration.emojis)
+            }
+            Mode::Permanent => decoration.emojis.to_string(),
+        };
+        tab.segments = reconcile(
+            &tab.segments,
+            &self.tab_format.segments(title, Some(&original)),
+        );
+        tab.segments.retain(Segment::is_pinned);
+        let rendered = render_decoration(
+            &self.tab_format,
+            &mut tab.segments,
+            &original,
+            &segment,
+            decoration,
+        );
+        tab.full_title = Some(original);
+        rendered
+    }
+
+    pub fn expect_pane_echo(&mut self, pane_ref: PaneRef, title: &str) {
+        self.panes.entry(pane_ref).or_default().echo = Some(ExpectedEcho::new(title));
+    }
+
+    pub fn expect_tab_echo(&mut self, tab_index: usize, title: &str) {
+        if let Some(tab_id) = self.tab_id(tab_index) {
+            self.tabs.entry(tab_id).or_default().echo = Some(ExpectedEcho::new(title));
+        }
+    }
+
+    fn take_pane_echoes(&mut self, pane_manifest: &PaneManifest) -> HashSet<PaneRef> {
+        let mut echoed = HashSet::new();
+        for pane in pane_manifest.panes.values().flat_map(|panes| panes.iter()) {
+            let pane_ref = pane_ref_from_pane_info(pane);
+            if let Some(state) = self.panes.get_mut(&pane_ref) {
+                if take_echo(&mut state.echo, &pane.title) {
+                    echoed.insert(pane_ref);
+                }
+            }
+        }
+        echoed
+    }
+
+    fn take_tab_echoes(&mut self, tab_infos: &[TabInfo]) -> HashSet<usize> {
+        let tab_panes = self.build_tab_panes();
+        let mut echoed = HashSet::new();
         for tab in tab_infos {
-            if !tab.active {
+            let Some(tab_id) = self.tab_index_tracker.tab_id(&tab_panes, tab.position) else {
                 continue;
+            };
+            if let Some(state) = self.tabs.get_mut(&tab_id) {
+                if take_echo(&mut state.echo, &tab.name) {
+                    echoed.insert(tab_id);
+                }
             }
-            let tab_index = tab.position;
+        }
+        echoed
+    }
 
-            let anchor_pane_id = self.tab_anchor_pane_id(tab_index);
+    pub fn schedule_pane_restore(&mut self, pane_ref: &PaneRef, title: &str) -> bool {
+        let focused = self.pane_manifest.as_ref().is_some_and(|manifest| {
+            manifest.panes.iter().any(|(position, panes)| {
+                panes.iter().any(|pane| {
+                    pane_matches(pane, pane_ref) && self.pane_has_visible_focus(*position, pane)
+                })
+            })
+        });
+        let cleaned = self
+            .pane_format
+            .with_pinned_segments(&self.pane_original(pane_ref, title), title);
+        let restore = (focused && cleaned != title).then(|| self.pending_restore(cleaned));
+        let due = restore.is_some();
+        self.panes
+            .entry(pane_ref.clone())
+            .or_default()
+            .pending_restore = restore;
+        due
+    }
+
+    pub fn schedule_tab_restore(&mut self, tab_index: usize, title: &str) -> bool {
+        let Some(tab_id) = self.tab_id(tab_index) else {
+            return false;
+        };
+        let active = self
+            .tab_infos
+            .iter()
+            .any(|tab| tab.position == tab_index && tab.active);
+        let cleaned = self
+            .tab_format
+            .with_pinned_segments(&self.tab_original(tab_index, title), title);
+        let restore = (active && cleaned != title).then(|| self.pending_restore(cleaned));
+        let due = restore.is_some();
+        self.tabs.entry(tab_id).or_default().pending_restore = restore;
+        due
+    }
 
-            let original_title = extract_original_title(&tab.name);
-            let cleaned_title = title_with_pinned_segments(&original_title, &tab.name);
+    fn pending_restore(&self, title: String) -> PendingRestore {
+        PendingRestore {
+            title,
+            due: Instant::now() + self.restore_delay,
+        }
+    }
 
-            if cleaned_title != tab.name {
-                self.pending_tab_restores.insert(
-                    tab_index,
-                    PendingTabRestore {
-                        title: cleaned_title,
-                        anchor_pane_id,
-                    },
-                );
-                set_timer = true;
+    fn observe_pane_titles(&mut self, pane_manifest: &PaneManifest, echoed: &HashSet<PaneRef>) {
+        for pane in pane_manifest.panes.values().flat_map(|panes| panes.iter()) {
+            let pane_ref = pane_ref_from_pane_info(pane);
+            let state = self.panes.entry(pane_ref.clone()).or_default();
+            if self
+                .pane_format
+                .original(&pane.title, state.full_title.as_ref())
+                != pane.title
+            {
+                continue;
+            }
+            if echoed.contains(&pane_ref) {
+                // Our own restore freezing the title; not the program changing it.
+                continue;
+            }
+            let origin = state.title_origin.get_or_insert_with(|| PaneTitleOrigin {
+                title: pane.title.clone(),
+                dynamic: is_default_pane_title(&pane.title),
+            });
+            if origin.title != pane.title {
+                origin.title = pane.title.clone();
+                origin.dynamic = true;
             }
         }
+    }
 
-        set_timer
+    /// Whether a restore to `title` can hand the title back to the program.
+    pub fn should_uncover_pane(&self, pane_ref: &PaneRef, title: &str) -> bool {
+        let Some(pane) = self.panes.get(pane_ref) else {
+            return false;
+        };
+        self.pane_format.original(title, pane.full_title.as_ref()) == title
+            && pane
+                .title_origin
+                .as_ref()
+                .is_some_and(|origin| origin.dynamic && origin.title == title)
     }
 
-    pub fn take_pending_pane_restores(&mut self) -> Vec<(PaneRef, String)> {
-        self.pending_pane_restores.drain().collect()
+    pub fn should_undo_tab_rename(&self, tab_index: usize, title: &str) -> bool {
+        is_default_tab_name(title) && self.focused_tab_index() == Some(tab_index)
     }
 
-    pub fn take_pending_tab_restores(&mut self) -> Vec<(usize, String)> {
-        let pending = std::mem::take(&mut self.pending_tab_restores);
-        let mut resolved = Vec::new();
-        let current_tabs: HashSet<usize> = self.tab_infos.iter().map(|tab| tab.position).collect();
+    pub fn follow_pane(&mut self, pane_ref: PaneRef, title: &str) {
+        let base = self.pane_original(&pane_ref, title);
+        let Some(decorations) = self
+            .pane_format
+            .decorations(&self.pane_format.original(title, Some(&base)), title)
+        else {
+            return;
+        };
+        let followed = FollowedPane {
+            base,
+            decorations: decorations.to_string(),
+            awaiting_base: false,
+        };
+        self.panes.entry(pane_ref).or_default().followed = Some(followed);
+    }
 
-        for (previous_index, restore) in pending {
-            if let Some(anchor_pane_id) = restore.anchor_pane_id {
-                if let Some(tab_index) = self.resolve_tab_index_from_pane_id(anchor_pane_id) {
-                    resolved.push((tab_index, restore.title));
-                } else if current_tabs.contains(&previous_index) {
-                    resolved.push((previous_index, restore.title));
-                } else {
-                    self.pending_tab_restores.insert(previous_index, restore);
-                }
-            } else if current_tabs.contains(&previous_index) {
-                resolved.push((previous_index, restore.title));
-            } else {
-                self.pending_tab_restores.insert(previous_index, restore);
+    pub fn animate_pane(&mut self, pane_ref: PaneRef, animation: Animation) {
+        self.panes.entry(pane_ref).or_default().animation = Some(animation);
+    }
+
+    pub fn animate_tab(&mut self, tab_id: usize, animation: Animation) {
+        self.tabs.entry(tab_id).or_default().animation = Some(animation);
+    }
+
+    pub fn next_animation_at(&self) -> Option<Instant> {
+        self.panes
+            .values()
+            .filter_map(|pane| pane.animation.as_ref())
+            .chain(self.tabs.values().filter_map(|tab| tab.animation.as_ref()))
+            .map(Animation::next_at)
+            .min()
+    }
+
+    pub fn step_animations(&mut self, budget: usize) -> AnimationFrames {
+        let now = Instant::now();
+        let panes = self.panes.iter().filter_map(|(pane_ref, pane)| {
+            let animation = pane.animation.as_ref()?;
+            Some((animation.next_at(), Animated::Pane(pane_ref.clone())))
+        });
+        let tabs = self.tabs.iter().filter_map(|(tab_id, tab)| {
+            let animation = tab.animation.as_ref()?;
+            Some((animation.next_at(), Animated::Tab(*tab_id)))
+        });
+        let mut due: Vec<(Instant, Animated)> = panes
+            .chain(tabs)
+            .filter(|(next_at, _)| *next_at <= now)
+            .collect();
+        due.sort_by_key(|(next_at, _)| *next_at);
+
+        let mut frames = AnimationFrames::default();
+        for (_, animated) in due.into_iter().take(budget) {
+            match animated {
+                Animated::Pane(pane_ref) => match self.next_pane_frame(&pane_ref, now) {
+                    Some(title) => frames.panes.push((pane_ref, title)),
+                    None => {
+                        if let Some(pane) = self.panes.get_mut(&pane_ref) {
+                            pane.animation = None;
+                        }
+                    }
+                },
+                Animated::Tab(tab_id) => match self.next_tab_frame(tab_id, now) {
+                    Some(frame) => frames.tabs.push(frame),
+                    None => {
+                        if let Some(tab) = self.tabs.get_mut(&tab_id) {
+                            tab.animation = None;
+                        }
+                    }
+                },
             }
         }
-
-        resolved
+        frames
     }
 
-    pub fn has_pending_tab_restores(&self) -> bool {
-        !self.pending_tab_restores.is_empty()
+    fn next_pane_frame(&mut self, pane_ref: &PaneRef, now: Instant) -> Option<String> {
+        let title = self.pane_effective_title(pane_ref)?;
+        let original = self.pane_original(pane_ref, &title);
+        let pane = self.panes.get_mut(pane_ref)?;
+        let mut segments = reconcile(
+            &pane.segments,
+            &self.pane_format.segments(&title, Some(&original)),
+        );
+        if !next_frame(&mut segments, pane.animation.as_mut()?, now) {
+            return None;
+        }
+        pane.segments = segments;
+        Some(self.pane_format.render(&original, &pane.segments))
     }
 
-    pub fn clear_pending_tab_restore(&mut self, tab_index: usize) {
-        let pending = std::mem::take(&mut self.pending_tab_restores);
-        let mut retained = HashMap::new();
+    fn next_tab_frame(&mut self, tab_id: usize, now: Instant) -> Option<(usize, String)> {
+        let tab_panes = self.build_tab_panes();
+        let tab_index = self.tab_index_tracker.tab_position(&tab_panes, tab_id)?;
+        let title = self.tab_effective_title(tab_index)?;
+        let original = self.tab_original(tab_index, &title);
+        let tab = self.tabs.get_mut(&tab_id)?;
+        let mut segments = reconcile(
+            &tab.segments,
+            &self.tab_format.segments(&title, Some(&original)),
+        );
+        if !next_frame(&mut segments, tab.animation.as_mut()?, now) {
+            return None;
+        }
+        tab.segments = segments;
+        Some((tab_index, self.tab_format.render(&original, &tab.segments)))
+    }
 
-        for (pending_index, restore) in pending {
-            let resolved_index = restore
-                .anchor_pane_id
-                .and_then(|anchor| self.resolve_tab_index_from_pane_id(anchor))
-                .unwrap_or(pending_index);
-            if resolved_index != tab_index {
-                retained.insert(pending_index, restore);
+    /// zellij does not report title changes of a renamed pane, so followed
+    /// panes are uncovered when the focus moves onto or off them.
+    pub fn take_followed_panes_to_uncover(&mut self) -> Vec<PaneRef> {
+        let mut uncover = Vec::new();
+        let Some(manifest) = self.pane_manifest.as_ref() else {
+            return uncover;
+        };
+        for (pane_ref, pane) in self.panes.iter_mut() {
+            if !std::mem::take(&mut pane.to_uncover) {
+                continue;
             }
+            let (Some(title), Some(followed)) =
+                (pane_title_in(manifest, pane_ref), pane.followed.as_mut())
+            else {
+                continue;
+            };
+            // Anything else on the pane (say, a restore in flight) is picked
+            // up by `sync_followed_panes` first.
+            if title
+                != self
+                    .pane_format
+                    .with_emojis(&followed.base, &followed.decorations)
+            {
+                continue;
+            }
+            followed.awaiting_base = true;
+            uncover.push(pane_ref.clone());
         }
+        uncover
+    }
+
+    pub fn sync_followed_panes(&mut self) -> Vec<(PaneRef, String)> {
+        let mut renames = Vec::new();
+        let Some(manifest) = self.pane_manifest.as_ref() else {
+            return renames;
+        };
 
-        self.pending_tab_restores = retained;
+        for (pane_ref, pane) in self.panes.iter_mut() {
+            let (Some(title), Some(followed)) =
+                (pane_title_in(manifest, pane_ref), pane.followed.as_mut())
+            else {
+                continue;
+            };
+            let shown = self.pane_format.original(&title, pane.full_title.as_ref());
+            let rendered = self
+                .pane_format
+                .with_emojis(&followed.base, &followed.decorations);
+
+            if followed.awaiting_base && shown == title {
+                followed.awaiting_base = false;
+                followed.base = title;
+                let rendered = self
+                    .pane_format
+                    .with_emojis(&followed.base, &followed.decorations);
+                // A restore scheduled before the program title showed up
+                // would bring the old one back.
+                if let Some(restore) = pane.pending_restore.as_mut() {
+                    restore.title = self
+                        .pane_format
+                        .with_pinned_segments(&followed.base, &rendered);
+                }
+                pane.full_title = Some(followed.base.clone());
+                renames.push((pane_ref.clone(), rendered));
+            } else if followed.awaiting_base && title == rendered {
+                // Our rendered title, reported before the empty name landed.
+                continue;
+            } else if shown == title {
+                // Every decoration was cleared; stop following.
+                pane.followed = None;
+            } else if let Some(decorations) = self.pane_format.decorations(&shown, &title) {
+                // Renamed over by a new decoration or a restore.
+                followed.awaiting_base = false;
+                followed.base = self
+                    .pane_format
+                    .expand(shown.clone(), pane.full_title.as_ref());
+                followed.decorations = decorations.to_string();
+            }
+        }
+        renames
     }
 
-    fn remap_tab_state_with_manifest(&mut self, pane_manifest: &PaneManifest) {
-        let mut manifest_positions: Vec<usize> = pane_manifest.panes.keys().copied().collect();
-        manifest_positions.sort_unstable();
-        let mut tab_positions: Vec<usize> = self.tab_infos.iter().map(|tab| tab.position).collect();
-        tab_positions.sort_unstable();
+    fn clean_focused_panes_on_focus(
+        &mut self,
+        pane_manifest: &PaneManifest,
+        echoed: &HashSet<PaneRef>,
+    ) -> bool {
+        let mut set_timer = false;
 
-        let pane_id_to_tab_index: HashMap<u32, usize> = pane_manifest
+        for (manifest_tab_position, pane) in pane_manifest
             .panes
             .iter()
-            .flat_map(|(tab_position, panes)| {
-                let resolved_tab_position = if tab_positions.contains(tab_position) {
-                    Some(*tab_position)
-                } else {
-                    manifest_positions
-                        .iter()
-                        .position(|position| position == tab_position)
-                        .and_then(|ordinal| tab_positions.get(ordinal).copied())
-                };
-
-                resolved_tab_position
-                    .map(|tab_position| panes.iter().map(move |pane| (pane.id, tab_position)))
-                    .into_iter()
-                    .flatten()
-            })
-            .collect();
+            .flat_map(|(position, panes)| panes.iter().map(move |pane| (*position, pane)))
+        {
+            if !self.pane_has_visible_focus(manifest_tab_position, pane) {
+                continue;
+            }
+
+            let pane_ref = pane_ref_from_pane_info(pane);
+            let pending = self
+                .panes
+                .get(&pane_ref)
+                .and_then(|state| state.pending_restore.as_ref())
+                .map(|restore| &restore.title);
+            if echoed.contains(&pane_ref) && pending.is_some() {
+                continue;
+            }
+            let original_title = self.pane_original(&pane_ref, &pane.title);
+            let cleaned_title = self
+                .pane_format
+                .with_pinned_segments(&original_title, &pane.title);
+
+            if cleaned_title != pane.title && pending != Some(&cleaned_title) {
+                let restore = self.pending_restore(cleaned_title);
+                self.panes.entry(pane_ref).or_default().pending_restore = Some(restore);
+                set_timer = true;
+            }
+        }
 
-        let mut remapped_restores = HashMap::new();
-        for (previous_index, restore) in self.pending_tab_restores.drain() {
-            if let Some(anchor_pane_id) = restore.anchor_pane_id {
-                if let Some(new_index) = pane_id_to_tab_index.get(&anchor_pane_id) {
-                    remapped_restores.insert(*new_index, restore);
-                } else {
-                    remapped_restores.insert(previous_index, restore);
+        set_timer
+    }
+
+    fn clean_focused_tabs_on_focus(
+        &mut self,
+        tab_infos: &[TabInfo],
+        echoed: &HashSet<usize>,
+    ) -> bool {
+        let mut set_timer = false;
+        let tab_panes = self.build_tab_panes();
+
+        for tab in tab_infos {
+            if !tab.active {
+                continue;
+            }
+            let Some(tab_id) = self.tab_index_tracker.tab_id(&tab_panes, tab.position) else {
+                continue;
+            };
+            let pending = self
+                .tabs
+                .get(&tab_id)
+                .and_then(|state| state.pending_restore.as_ref())
+                .map(|restore| &restore.title);
+            if echoed.contains(&tab_id) && pending.is_some() {
+                continue;
+            }
+
+            let original_title = self.tab_original(tab.position, &tab.name);
+            let cleaned_title = self
+                .tab_format
+                .with_pinned_segments(&original_title, &tab.name);
+
+            if cleaned_title != tab.name && pending != Some(&cleaned_title) {
+                let restore = self.pending_restore(cleaned_title);
+                self.tabs.entry(tab_id).or_default().pending_restore = Some(restore);
+                set_timer = true;
+            }
+        }
+
+        set_timer
+    }
+
+    fn track_focus(&mut self) {
+        let now = Instant::now();
+        let focused_pane = self.focused_pane_ref();
+        if self.pane_focus_since.as_ref().map(|(pane_ref, _)| pane_ref) != focused_pane.as_ref() {
+            let left = self.pane_focus_since.take().map(|(pane_ref, _)| pane_ref);
+            for pane_ref in left.into_iter().chain(focused_pane.clone()) {
+                if let Some(pane) = self.panes.get_mut(&pane_ref) {
+                    pane.to_uncover = pane.followed.is_some();
+                }
+            }
+            self.pane_focus_since = focused_pane.map(|pane_ref| (pane_ref, now));
+        }
+        let focused_tab = self
+            .focused_tab_index()
+            .and_then(|tab_index| self.tab_id(tab_index));
+        if self.tab_focus_since.map(|(tab_id, _)| tab_id) != focused_tab {
+            self.tab_focus_since = focused_tab.map(|tab_id| (tab_id, now));
+        }
+    }
+
+    /// Leaving a target before `min_dwell` drops its restore until the next visit.
+    pub fn take_pending_pane_restores(&mut self) -> Vec<(PaneRef, String)> {
+        let now = Instant::now();
+        let focus = self.pane_focus_since.as_ref();
+        let mut ready = Vec::new();
+        for (pane_ref, pane) in self.panes.iter_mut() {
+            let Some(restore) = pane.pending_restore.take() else {
+                continue;
+            };
+            if restore.due > now {
+                pane.pending_restore = Some(restore);
+                continue;
+            }
+            match dwell(focus, pane_ref, self.min_dwell) {
+                Dwell::Done => {
+                    pane.restored();
+                    ready.push((pane_ref.clone(), restore.title));
+                }
+                Dwell::Staying(_) => pane.pending_restore = Some(restore),
+                Dwell::Left => {}
+            }
+        }
+        ready
+    }
+
+    pub fn take_pending_tab_restores(&mut self) -> Vec<(usize, String)> {
+        let now = Instant::now();
+        let tab_panes = self.build_tab_panes();
+        let focus = self.tab_focus_since.as_ref();
+        let mut resolved = Vec::new();
+        for (tab_id, tab) in self.tabs.iter_mut() {
+            let Some(restore) = tab.pending_restore.take() else {
+                continue;
+            };
+            if restore.due > now {
+                tab.pending_restore = Some(restore);
+                continue;
+            }
+            match dwell(focus, tab_id, self.min_dwell) {
+                Dwell::Done => {}
+                Dwell::Staying(_) => {
+                    tab.pending_restore = Some(restore);
+                    continue;
+                }
+                Dwell::Left => continue,
+            }
+            match self.tab_index_tracker.tab_position(&tab_panes, *tab_id) {
+                Some(tab_index) => {
+                    tab.restored();
+                    resolved.push((tab_index, restore.title));
+                }
+                // Retry once the tab can be found again.
+                None => {
+                    tab.pending_restore = Some(PendingRestore {
+                        due: now + self.restore_delay,
+                        ..restore
+                    })
                 }
-            } else {
-                remapped_restores.insert(previous_index, restore);
             }
         }
-        self.pending_tab_restores = remapped_restores;
+        resolved
+    }
+
+    /// When the earliest pending restore can go through.
+    pub fn next_restore_at(&self) -> Option<Instant> {
+        let panes = self.panes.iter().filter_map(|(pane_ref, pane)| {
+            let restore = pane.pending_restore.as_ref()?;
+            Some(restore_at(
+                restore,
+                self.pane_focus_since.as_ref(),
+                pane_ref,
+                self.min_dwell,
+            ))
+        });
+        let tabs = self.tabs.iter().filter_map(|(tab_id, tab)| {
+            let restore = tab.pending_restore.as_ref()?;
+            Some(restore_at(
+                restore,
+                self.tab_focus_since.as_ref(),
+                tab_id,
+                self.min_dwell,
+            ))
+        });
+        panes.chain(tabs).min()
     }
 
     fn manifest_tab_position_for_tab_position(&self, tab_position: usize) -> Option<usize> {
@@ -553,30 +1371,484 @@ impl EmotitleState {
     }
 }
 
-pub fn title_with_emojis(original_title: &str, emojis: &str) -> String {
-    format!("{original_title} | {emojis}")
+pub const DEFAULT_TITLE_TEMPLATE: &str = "{title}{separator}{emojis}";
+pub const DEFAULT_SEPARATOR: &str = " | ";
+const ELLIPSIS: &str = "…";
+const REPEAT_MARK: char = '×';
+
+#[derive(Debug, Clone)]
+pub struct TitleFormat {
+    pub separator: String,
+    pub max_width: Option<usize>,
+    pub max_segments: Option<usize>,
+    prefix: String,
+    infix: String,
+    suffix: String,
+    emojis_first: bool,
 }
 
-pub fn extract_original_title(title: &str) -> String {
-    title.split(" | ").next().unwrap_or(title).to_string()
+impl Default for TitleFormat {
+    fn default() -> Self {
+        Self::new(DEFAULT_TITLE_TEMPLATE, DEFAULT_SEPARATOR)
+            .expect("default title template is valid")
+    }
 }
 
-pub fn title_with_pinned_segments(original_title: &str, current_title: &str) -> String {
-    let Some(rest) = current_title.strip_prefix(original_title) else {
-        return original_title.to_string();
-    };
+impl TitleFormat {
+    pub fn new(template: &str, separator: &str) -> Result<Self, String> {
+        let template = template.replace("{separator}", separator);
+        let (Some(title_at), Some(emojis_at)) =
+            (template.find("{title}"), template.find("{emojis}"))
+        else {
+            return Err(format!(
+                "title template {template:?} must contain {{title}} and {{emojis}}"
+            ));
+        };
+        if template.matches("{title}").count() > 1 || template.matches("{emojis}").count() > 1 {
+            return Err(format!(
+                "title template {template:?} must contain {{title}} and {{emojis}} only once"
+            ));
+        }
+
+        let emojis_first = emojis_at < title_at;
+        let (first, first_len, second, second_len) = if emojis_first {
+            (emojis_at, "{emojis}".len(), title_at, "{title}".len())
+        } else {
+            (title_at, "{title}".len(), emojis_at, "{emojis}".len())
+        };
+        let infix = &template[first + first_len..second];
+        if infix.is_empty() {
+            return Err(format!(
+                "title template {template:?} needs text between {{title}} and {{emojis}}"
+            ));
+        }
 
-    let pinned_segments: Vec<String> = rest
-        .split(" | ")
-        .map(str::trim)
-        .filter(|segment| !segment.is_empty() && segment.starts_with('📌'))
-        .map(str::to_string)
+        Ok(Self {
+            separator: separator.to_string(),
+            max_width: None,
+            max_segments: None,
+            prefix: template[..first].to_string(),
+            infix: infix.to_string(),
+            suffix: template[second + second_len..].to_string(),
+            emojis_first,
+        })
+    }
+
+    pub fn with_emojis(&self, original_title: &str, emojis: &str) -> String {
+        let original_title = self.fit(original_title, emojis);
+        let original_title = original_title.as_str();
+        let (first, second) = if self.emojis_first {
+            (emojis, original_title)
+        } else {
+            (original_title, emojis)
+        };
+        format!(
+            "{}{first}{}{second}{}",
+            self.prefix, self.infix, self.suffix
+        )
+    }
+
+    /// A slotted segment replaces its slot in place; others go last, replacing
+    /// an unslotted copy. Returns the segments evicted for `max_segments` or
+    /// `max_width`, lowest priority and oldest first.
+    fn append(
+        &self,
+        segments: &mut Vec<Segment>,
+        segment: Segment,
+        excluded: &[String],
+    ) -> Vec<String> {
+        segments.retain_mut(|shown| match without_members(&shown.text, excluded) {
+            Some(text) => {
+                shown.text = text;
+                true
+            }
+            None => false,
+        });
+        let replaces = segment.slot.as_ref().and_then(|slot| {
+            segments
+                .iter()
+                .position(|shown| shown.slot.as_ref() == Some(slot))
+        });
+        match replaces {
+            Some(at) => segments[at] = segment,
+            None => {
+                segments.retain(|shown| {
+                    shown.slot.is_some() || uncounted(&shown.text) != uncounted(&segment.text)
+                });
+                segments.push(segment);
+            }
+        }
+
+        let mut evicted = Vec::new();
+        while self.max_segments.is_some_and(|max| segments.len() > max) {
+            let Some(victim) = eviction_victim(segments, Priority::High) else {
+                break;
+            };
+            evicted.push(segments.remove(victim).text);
+        }
+        while self.leaves_no_room(segments) {
+            let Some(victim) = eviction_victim(segments, Priority::Low) else {
+                break;
+            };
+            evicted.push(segments.remove(victim).text);
+        }
+        segments.sort_by_key(|segment| Reverse(segment.priority));
+        evicted
+    }
+
+    fn render(&self, original_title: &str, segments: &[Segment]) -> String {
+        if segments.is_empty() {
+            original_title.to_string()
+        } else {
+            self.with_emojis(original_title, &self.join(segments))
+        }
+    }
+
+    fn join(&self, segments: &[Segment]) -> String {
+        segments
+            .iter()
+            .map(|segment| segment.text.as_str())
+            .collect::<Vec<_>>()
+            .join(&self.separator)
+    }
+
+    pub fn contains_segment(&self, title: &str, original_title: &str, emojis: &str) -> bool {
+        self.segments(title, Some(&original_title.to_string()))
+            .iter()
+            .any(|segment| uncounted(segment) == emojis)
+    }
+
+    pub fn segments<'a>(&self, title: &'a str, full: Option<&String>) -> Vec<&'a str> {
+        self.decorations(&self.original(title, full), title)
+            .map(|decorations| {
+                decorations
+                    .split(self.separator.as_str())
+                    .map(str::trim)
+                    .filter(|segment| !segment.is_empty())
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    pub fn original(&self, title: &str, full: Option<&String>) -> String {
+        self.strip_decorations(title, full)
+            .unwrap_or(title)
+            .to_string()
+    }
+
+    pub fn decorations<'a>(&self, original_title: &str, title: &'a str) -> Option<&'a str> {
+        let inner = title
+            .strip_prefix(self.prefix.as_str())?
+            .strip_suffix(self.suffix.as_str())?;
+        if self.emojis_first {
+            inner
+                .strip_suffix(original_title)?
+                .strip_suffix(self.infix.as_str())
+        } else {
+            inner
+                .strip_prefix(original_title)?
+                .strip_prefix(self.infix.as_str())
+        }
+    }
+
+    pub fn expand(&self, shown: String, full: Option<&String>) -> String {
+        match full {
+            Some(full) if shown != *full && is_shortened(full, &shown) => full.clone(),
+            _ => shown,
+        }
+    }
+
+    pub fn with_pinned_segments(&self, original_title: &str, current_title: &str) -> String {
+        let shown = self.original(current_title, Some(&original_title.to_string()));
+        let Some(decorations) = self.decorations(&shown, current_title) else {
+            return original_title.to_string();
+        };
+
+        let pinned_segments: Vec<&str> = decorations
+            .split(self.separator.as_str())
+            .map(str::trim)
+            .filter(|segment| !segment.is_empty() && segment.starts_with('📌'))
+            .collect();
+
+        if pinned_segments.is_empty() {
+            original_title.to_string()
+        } else {
+            self.with_emojis(original_title, &pinned_segments.join(&self.separator))
+        }
+    }
+
+    fn decorations_width(&self, emojis: &str) -> usize {
+        [&self.prefix, &self.infix, &self.suffix]
+            .iter()
+            .map(|part| part.width())
+            .sum::<usize>()
+            + emojis.width()
+    }
+
+    fn leaves_no_room(&self, segments: &[Segment]) -> bool {
+        self.max_width.is_some_and(|max_width| {
+            self.decorations_width(&self.join(segments)) + ELLIPSIS.width() > max_width
+        })
+    }
+
+    fn fit(&self, original_title: &str, emojis: &str) -> String {
+        let Some(max_width) = self.max_width else {
+            return original_title.to_string();
+        };
+        let available = max_width.saturating_sub(self.decorations_width(emojis));
+        if original_title.width() <= available {
+            return original_title.to_string();
+        }
+
+        let budget = available.saturating_sub(ELLIPSIS.width());
+        let mut kept = String::new();
+        let mut kept_width = 0;
+        for grapheme in original_title.graphemes(true) {
+            kept_width += grapheme.width();
+            if kept_width > budget {
+                break;
+            }
+            kept.push_str(grapheme);
+        }
+        format!("{}{ELLIPSIS}", kept.trim_end())
+    }
+
+    fn looks_decorated(&self, decorations: &str) -> bool {
+        decorations
+            .split(self.separator.as_str())
+            .all(|segment| !segment.contains(self.infix.as_str()) && !segment.is_ascii())
+    }
+
+    fn strip_decorations<'a>(&self, title: &'a str, full: Option<&String>) -> Option<&'a str> {
+        let inner = title
+            .strip_prefix(self.prefix.as_str())?
+            .strip_suffix(self.suffix.as_str())?;
+        if !self.emojis_first {
+            // The infix may also occur in the title (`Pane #1 🚀` with a
+            // plain space), so split where the title we decorated ends, or
+            // else before the last run of what can pass for segments.
+            let mut splits = inner
+                .match_indices(self.infix.as_str())
+                .map(|(at, infix)| (&inner[..at], &inner[at + infix.len()..]));
+            let found = match full {
+                Some(full) => splits.clone().find(|(shown, _)| is_shortened(full, shown)),
+                None => None,
+            };
+            return found
+                .or_else(|| splits.find(|(_, decorations)| self.looks_decorated(decorations)))
+                .map(|(shown, _)| shown);
+        }
+
+        // Titles such as `🏠 home` look just like decorations in front of a
+        // title, so only a target we decorated is split, and only where the
+        // rest is the title we decorated it around (or what `max_width` left
+        // of it). The infix may also occur inside the segments, hence the
+        // search from the left.
+        let full = full?;
+        inner
+            .match_indices(self.infix.as_str())
+            .filter(|(at, _)| *at > 0)
+            .map(|(at, infix)| &inner[at + infix.len()..])
+            .find(|shown| is_shortened(full, shown))
+    }
+}
+
+fn is_shortened(full: &str, shown: &str) -> bool {
+    shown == full
+        || shown
+            .strip_suffix(ELLIPSIS)
+            .is_some_and(|kept| full.starts_with(kept))
+}
+
+fn uncounted(segment: &str) -> &str {
+    match segment.rsplit_once(REPEAT_MARK) {
+        Some((emojis, count)) if !count.is_empty() && count.chars().all(|c| c.is_ascii_digit()) => {
+            emojis
+        }
+        _ => segment,
+    }
+}
+
+/// Members only match whole graphemes, so removing `👨` leaves `👨‍💻` alone.
+fn without_members(segment: &str, excluded: &[String]) -> Option<String> {
+    let emojis = uncounted(segment);
+    let count = &segment[emojis.len()..];
+    let graphemes: Vec<&str> = emojis.graphemes(true).collect();
+    let members: Vec<Vec<&str>> = excluded
+        .iter()
+        .map(|member| member.graphemes(true).collect())
+        .filter(|member: &Vec<&str>| !member.is_empty())
         .collect();
 
-    if pinned_segments.is_empty() {
-        original_title.to_string()
+    let mut kept = String::new();
+    let mut at = 0;
+    while at < graphemes.len() {
+        match members
+            .iter()
+            .find(|member| graphemes[at..].starts_with(member))
+        {
+            Some(member) => at += member.len(),
+            None => {
+                kept.push_str(graphemes[at]);
+                at += 1;
+            }
+        }
+    }
+    if kept.is_empty() || kept == "📌" {
+        return None;
+    }
+    Some(format!("{kept}{count}"))
+}
+
+fn render_decoration(
+    format: &TitleFormat,
+    segments: &mut Vec<Segment>,
+    original: &str,
+    segment: &str,
+    decoration: &Decoration,
+) -> (String, Vec<String>) {
+    let segment = Segment {
+        text: segment.to_string(),
+        priority: decoration.priority,
+        slot: decoration.slot.map(str::to_string),
+    };
+    let evicted = format.append(segments, segment, decoration.excluded);
+    (format.render(original, segments), evicted)
+}
+
+/// Matches the segments shown on a target with the ones last rendered there,
+/// so identical texts keep their own priority and slot.
+fn reconcile(known: &[Segment], shown: &[&str]) -> Vec<Segment> {
+    let mut unmatched: Vec<Option<&Segment>> = known.iter().map(Some).collect();
+    shown
+        .iter()
+        .map(|text| {
+            unmatched
+                .iter_mut()
+                .find(|segment| segment.is_some_and(|segment| segment.text == *text))
+                .and_then(Option::take)
+                .cloned()
+                .unwrap_or_else(|| Segment {
+                    text: text.to_string(),
+                    priority: Priority::default(),
+                    slot: None,
+                })
+        })
+        .collect()
+}
+
+fn next_frame(segments: &mut [Segment], animation: &mut Animation, now: Instant) -> bool {
+    let Some(segment) = segments.iter_mut().find(|segment| {
+        segment.slot.as_deref() == animation.slot() && animation.shows(uncounted(&segment.text))
+    }) else {
+        return false;
+    };
+    let count = segment.text[uncounted(&segment.text).len()..].to_string();
+    segment.text = format!("{}{count}", animation.advance(now));
+    true
+}
+
+fn eviction_victim(segments: &[Segment], up_to: Priority) -> Option<usize> {
+    segments
+        .iter()
+        .enumerate()
+        .filter(|(_, segment)| !segment.is_pinned())
+        .map(|(index, segment)| (segment.priority, index))
+        .filter(|(priority, _)| *priority <= up_to)
+        .min()
+        .map(|(_, index)| index)
+}
+
+fn count_repeat(counts: &mut HashMap<String, usize>, shown: bool, emojis: &str) -> String {
+    let count = if shown {
+        counts.get(emojis).copied().unwrap_or(1) + 1
     } else {
-        format!("{original_title} | {}", pinned_segments.join(" | "))
+        1
+    };
+    counts.insert(emojis.to_string(), count);
+    if count > 1 {
+        format!("{emojis}{REPEAT_MARK}{count}")
+    } else {
+        emojis.to_string()
+    }
+}
+
+enum Dwell {
+    Done,
+    Staying(Duration),
+    Left,
+}
+
+fn dwell<K: PartialEq>(focus: Option<&(K, Instant)>, target: &K, min_dwell: Duration) -> Dwell {
+    if min_dwell.is_zero() {
+        return Dwell::Done;
+    }
+    match focus {
+        Some((focused, since)) if focused == target => {
+            match min_dwell.checked_sub(since.elapsed()) {
+                Some(remaining) if !remaining.is_zero() => Dwell::Staying(remaining),
+                _ => Dwell::Done,
+            }
+        }
+        _ => Dwell::Left,
+    }
+}
+
+fn restore_at<K: PartialEq>(
+    restore: &PendingRestore,
+    focus: Option<&(K, Instant)>,
+    target: &K,
+    min_dwell: Duration,
+) -> Instant {
+    match dwell(focus, target, min_dwell) {
+        Dwell::Staying(remaining) => restore.due.max(Instant::now() + remaining),
+        Dwell::Done | Dwell::Left => restore.due,
+    }
+}
+
+fn is_default_tab_name(title: &str) -> bool {
+    title
+        .strip_prefix("Tab #")
+        .is_some_and(|number| !number.is_empty() && number.chars().all(|c| c.is_ascii_digit()))
+}
+
+fn is_default_pane_title(title: &str) -> bool {
+    title
+        .strip_prefix("Pane #")
+        .is_some_and(|number| !number.is_empty() && number.chars().all(|c| c.is_ascii_digit()))
+}
+
+fn keys_overlap(left: &[PaneKey], right: &[PaneKey]) -> bool {
+    left.iter().any(|key| right.contains(key))
+}
+
+fn tab_position_for_keys(
+    tab_panes: &HashMap<usize, Vec<PaneKey>>,
+    pane_keys: &[PaneKey],
+) -> Option<usize> {
+    tab_panes
+        .iter()
+        .find(|(_, keys)| keys_overlap(keys, pane_keys))
+        .map(|(position, _)| *position)
+}
+
+fn pane_title_in(manifest: &PaneManifest, pane_ref: &PaneRef) -> Option<String> {
+    manifest
+        .panes
+        .values()
+        .flat_map(|panes| panes.iter())
+        .find(|pane| pane_matches(pane, pane_ref))
+        .map(|pane| pane.title.clone())
+}
+
+fn take_echo(echo: &mut Option<ExpectedEcho>, title: &str) -> bool {
+    match echo.take() {
+        Some(expected) if expected.issued_at.elapsed() >= ECHO_TIMEOUT => false,
+        Some(expected) if expected.title == title => true,
+        other => {
+            *echo = other;
+            false
+        }
     }
 }
 
@@ -16,25 +16,52 @@ pub struct TabIndexEvent {
     pub event_type: TabIndexEventType,
     pub pane_keys: Vec<PaneKey>,
     pub internal_index: usize,
+    pub tab_id: Option<usize>,
+    pub position: Option<usize>,
 }
 
 #[derive(Serialize, Clone, Debug, PartialEq, Eq)]
+#[allow(clippy::enum_variant_names)]
 pub enum TabIndexEventType {
     TabAdded,
     TabKeyUpdated,
     TabRemoved,
+    TabReindexed,
+    TabMoved,
+    TabRenameAnomaly,
 }
 
 #[derive(Serialize)]
 pub struct InternalIndexEntry {
     pub pane_keys: Vec<PaneKey>,
     pub internal_index: usize,
+    pub verified: bool,
+    pub tab_id: usize,
+    pub position: Option<usize>,
+}
+
+/// `internal_index` is our guess of zellij's own tab index, which only grows
+/// as tabs are created. The guess is exact when the plugin saw every tab being
+/// created, but drifts when it is loaded into a session whose earlier tabs
+/// were already closed, so it is only trusted once a rename through it has
+/// been observed to land on the right tab.
+///
+/// `tab_id` on the other hand is ours alone: it never changes for the life of
+/// the tab, whatever happens to its panes, position or `internal_index`, so
+/// state that belongs to a tab is keyed by it.
+#[derive(Clone, Debug)]
+struct TrackedTab {
+    tab_id: usize,
+    internal_index: usize,
+    verified: bool,
+    position: Option<usize>,
 }
 
 #[derive(Default)]
 pub struct TabIndexTracker {
-    internal_index_map: HashMap<Vec<PaneKey>, usize>,
+    internal_index_map: HashMap<Vec<PaneKey>, TrackedTab>,
     next_internal_index: usize,
+    next_tab_id: usize,
     event_history: VecDeque<TabIndexEvent>,
     event_seq: u64,
     removed_keys: HashSet<Vec<PaneKey>>,
@@ -60,7 +87,7 @@ impl TabIndexTracker {
                         .iter()
                         .any(|current| current.iter().any(|key| old_keys.contains(key)))
             })
-            .map(|(keys, &idx)| (keys.clone(), idx))
+            .map(|(keys, tracked)| (keys.clone(), tracked.internal_index))
             .collect();
 
         for (pane_keys, internal_index) in newly_removed {
@@ -88,6 +115,12 @@ impl TabIndexTracker {
         tab_infos: &[zellij_tile::prelude::TabInfo],
         tab_panes: &HashMap<usize, Vec<PaneKey>>,
     ) {
+        let previous_positions: HashMap<usize, usize> = self
+            .live_entries()
+            .filter_map(|(_, tracked)| Some((tracked.tab_id, tracked.position?)))
+            .collect();
+        let mut current_positions: HashMap<usize, usize> = HashMap::new();
+
         for tab in tab_infos {
             let Some(pane_keys) = tab_panes.get(&tab.position) else {
                 continue;
@@ -96,15 +129,15 @@ impl TabIndexTracker {
                 continue;
             }
 
-            let existing = self
-                .internal_index_map
-                .iter()
-                .find(|(old_keys, _)| old_keys.iter().any(|key| pane_keys.contains(key)));
+            let existing = self.find_entry(pane_keys);
 
-            if let Some((old_keys, &internal_index)) = existing {
+            if let Some((old_keys, tracked)) = existing {
                 if old_keys != pane_keys {
-                    self.internal_index_map
-                        .insert(pane_keys.clone(), internal_index);
+                    let old_keys = old_keys.clone();
+                    let tracked = tracked.clone();
+                    let internal_index = tracked.internal_index;
+                    self.internal_index_map.remove(&old_keys);
+                    self.internal_index_map.insert(pane_keys.clone(), tracked);
                     self.record_event(
                         TabIndexEventType::TabKeyUpdated,
                         pane_keys.clone(),
@@ -114,14 +147,41 @@ impl TabIndexTracker {
             } else if !self.internal_index_map.contains_key(pane_keys) {
                 let internal_index = self.next_internal_index;
                 self.next_internal_index += 1;
-                self.internal_index_map
-                    .insert(pane_keys.clone(), internal_index);
+                let tab_id = self.next_tab_id;
+                self.next_tab_id += 1;
+                self.internal_index_map.insert(
+                    pane_keys.clone(),
+                    TrackedTab {
+                        tab_id,
+                        internal_index,
+                        verified: false,
+                        position: Some(tab.position),
+                    },
+                );
                 self.record_event(
                     TabIndexEventType::TabAdded,
                     pane_keys.clone(),
                     internal_index,
                 );
             }
+
+            if let Some(keys) = self.find_entry(pane_keys).map(|(keys, _)| keys.clone()) {
+                if let Some(tracked) = self.internal_index_map.get_mut(&keys) {
+                    tracked.position = Some(tab.position);
+                    current_positions.insert(tracked.tab_id, tab.position);
+                }
+            }
+        }
+
+        for tab_id in moved_tabs(&previous_positions, &current_positions) {
+            let Some((keys, internal_index)) = self
+                .live_entries()
+                .find(|(_, tracked)| tracked.tab_id == tab_id)
+                .map(|(keys, tracked)| (keys.clone(), tracked.internal_index))
+            else {
+                continue;
+            };
+            self.record_event(TabIndexEventType::TabMoved, keys, internal_index);
         }
     }
 
@@ -131,9 +191,12 @@ impl TabIndexTracker {
         pane_keys: Vec<PaneKey>,
         internal_index: usize,
     ) {
+        let tracked = self.internal_index_map.get(&pane_keys);
         let event = TabIndexEvent {
             seq: self.event_seq,
             event_type,
+            tab_id: tracked.map(|tracked| tracked.tab_id),
+            position: tracked.and_then(|tracked| tracked.position),
             pane_keys,
             internal_index,
         };
@@ -155,21 +218,261 @@ impl TabIndexTracker {
         tab_index: usize,
     ) -> Option<u32> {
         let pane_keys = tab_panes.get(&tab_index)?;
-        let internal_index = self
+        let (_, tracked) = self.find_entry(pane_keys)?;
+        Some((tracked.internal_index + 1) as u32)
+    }
+
+    pub fn tab_id(
+        &self,
+        tab_panes: &HashMap<usize, Vec<PaneKey>>,
+        tab_index: usize,
+    ) -> Option<usize> {
+        let pane_keys = tab_panes.get(&tab_index)?;
+        self.find_entry(pane_keys)
+            .map(|(_, tracked)| tracked.tab_id)
+    }
+
+    /// Where the tab currently sits, looked up through its panes so that it
+    /// follows the tab through moves and swaps.
+    pub fn tab_position(
+        &self,
+        tab_panes: &HashMap<usize, Vec<PaneKey>>,
+        tab_id: usize,
+    ) -> Option<usize> {
+        let (keys, _) = self
+            .live_entries()
+            .find(|(_, tracked)| tracked.tab_id == tab_id)?;
+        tab_panes
+            .iter()
+            .find(|(_, current)| current.iter().any(|key| keys.contains(key)))
+            .map(|(position, _)| *position)
+    }
+
+    pub fn is_tab_removed(&self, tab_id: usize) -> bool {
+        !self
+            .live_entries()
+            .any(|(_, tracked)| tracked.tab_id == tab_id)
+    }
+
+    pub fn is_verified(&self, pane_keys: &[PaneKey]) -> bool {
+        self.find_entry(pane_keys)
+            .is_some_and(|(_, tracked)| tracked.verified)
+    }
+
+    /// A rename through this tab's index landed on this tab.
+    pub fn mark_verified(&mut self, pane_keys: &[PaneKey]) {
+        if let Some(keys) = self.find_entry(pane_keys).map(|(keys, _)| keys.clone()) {
+            if let Some(tracked) = self.internal_index_map.get_mut(&keys) {
+                tracked.verified = true;
+            }
+        }
+    }
+
+    /// A rename through `internal_index` landed on the tab owning `pane_keys`.
+    /// That tab takes the index, and any unverified tab guessed at it or
+    /// above moves out of the way.
+    pub fn calibrate(&mut self, pane_keys: &[PaneKey], internal_index: usize) {
+        let Some(keys) = self.find_entry(pane_keys).map(|(keys, _)| keys.clone()) else {
+            return;
+        };
+
+        let mut moved = Vec::new();
+        let displaced: Vec<Vec<PaneKey>> = self
+            .live_entries()
+            .filter(|(other, tracked)| **other != keys && tracked.internal_index == internal_index)
+            .map(|(other, _)| other.clone())
+            .collect();
+        for other in &displaced {
+            if let Some(tracked) = self.internal_index_map.get_mut(other) {
+                tracked.verified = false;
+            }
+        }
+
+        if let Some(tracked) = self.internal_index_map.get_mut(&keys) {
+            if tracked.internal_index != internal_index {
+                moved.push(keys.clone());
+            }
+            tracked.internal_index = internal_index;
+            tracked.verified = true;
+        }
+
+        moved.extend(self.shift_unverified_from(internal_index, &keys));
+        self.record_reindexed(moved);
+    }
+
+    /// Nothing answered to the index guessed for `pane_keys`, so zellij's
+    /// index for that tab (and every unverified tab after it) is higher. A
+    /// verified index is left alone: only a rename seen landing on another
+    /// tab moves it, through [`Self::calibrate`].
+    pub fn skip_missing_index(&mut self, pane_keys: &[PaneKey]) {
+        let Some((keys, tracked)) = self
+            .find_entry(pane_keys)
+            .map(|(keys, tracked)| (keys.clone(), tracked.clone()))
+        else {
+            return;
+        };
+        if tracked.verified {
+            return;
+        }
+        let verified_indices: HashSet<usize> = self
+            .live_entries()
+            .filter(|(other_keys, other)| **other_keys != keys && other.verified)
+            .map(|(_, other)| other.internal_index)
+            .collect();
+        let mut internal_index = tracked.internal_index + 1;
+        while verified_indices.contains(&internal_index) {
+            internal_index += 1;
+        }
+        if let Some(entry) = self.internal_index_map.get_mut(&keys) {
+            entry.internal_index = internal_index;
+        }
+        let mut moved = vec![keys.clone()];
+        moved.extend(self.shift_unverified_from(internal_index, &keys));
+        self.record_reindexed(moved);
+    }
+
+    /// Bumps unverified tabs at or above `from` so that none of them shares an
+    /// index with `pinned` or with any other live tab. Returns the moved keys.
+    fn shift_unverified_from(&mut self, from: usize, pinned: &[PaneKey]) -> Vec<Vec<PaneKey>> {
+        let mut taken: HashSet<usize> = self
+            .live_entries()
+            .filter(|(keys, tracked)| tracked.verified || keys.as_slice() == pinned)
+            .map(|(_, tracked)| tracked.internal_index)
+            .collect();
+
+        let mut candidates: Vec<(Vec<PaneKey>, usize)> = self
+            .live_entries()
+            .filter(|(keys, tracked)| {
+                !tracked.verified && keys.as_slice() != pinned && tracked.internal_index >= from
+            })
+            .map(|(keys, tracked)| (keys.clone(), tracked.internal_index))
+            .collect();
+        candidates.sort_by_key(|(_, index)| *index);
+
+        let mut moved = Vec::new();
+        let mut floor = from;
+        for (keys, index) in candidates {
+            let mut new_index = index.max(floor);
+            while taken.contains(&new_index) {
+                new_index += 1;
+            }
+            taken.insert(new_index);
+            floor = new_index + 1;
+            if new_index != index {
+                if let Some(tracked) = self.internal_index_map.get_mut(&keys) {
+                    tracked.internal_index = new_index;
+                }
+                moved.push(keys);
+            }
+        }
+
+        let highest = self
             .internal_index_map
+            .values()
+            .map(|tracked| tracked.internal_index + 1)
+            .max()
+            .unwrap_or(0);
+        self.next_internal_index = self.next_internal_index.max(highest);
+        moved
+    }
+
+    /// A rename sent through `rename_target` for the tab owning `pane_keys`
+    /// did not land on it.
+    pub fn record_rename_anomaly(&mut self, pane_keys: &[PaneKey], rename_target: u32) {
+        let Some(keys) = self.find_entry(pane_keys).map(|(keys, _)| keys.clone()) else {
+            return;
+        };
+        self.record_event(
+            TabIndexEventType::TabRenameAnomaly,
+            keys,
+            rename_target.saturating_sub(1) as usize,
+        );
+    }
+
+    fn record_reindexed(&mut self, moved: Vec<Vec<PaneKey>>) {
+        for keys in moved {
+            let Some(internal_index) = self
+                .internal_index_map
+                .get(&keys)
+                .map(|tracked| tracked.internal_index)
+            else {
+                continue;
+            };
+            self.record_event(TabIndexEventType::TabReindexed, keys, internal_index);
+        }
+    }
+
+    fn find_entry(&self, pane_keys: &[PaneKey]) -> Option<(&Vec<PaneKey>, &TrackedTab)> {
+        let overlaps = |old_keys: &Vec<PaneKey>| old_keys.iter().any(|key| pane_keys.contains(key));
+        self.live_entries()
+            .find(|(old_keys, _)| overlaps(old_keys))
+            .or_else(|| {
+                self.internal_index_map
+                    .iter()
+                    .find(|(old_keys, _)| overlaps(old_keys))
+            })
+    }
+
+    fn live_entries(&self) -> impl Iterator<Item = (&Vec<PaneKey>, &TrackedTab)> {
+        self.internal_index_map
             .iter()
-            .find(|(old_keys, _)| old_keys.iter().any(|key| pane_keys.contains(key)))
-            .map(|(_, &idx)| idx)?;
-        Some((internal_index + 1) as u32)
+            .filter(|(keys, _)| !self.removed_keys.contains(*keys))
     }
 
     pub fn get_debug_entries(&self) -> Vec<InternalIndexEntry> {
         self.internal_index_map
             .iter()
-            .map(|(pane_keys, &internal_index)| InternalIndexEntry {
+            .map(|(pane_keys, tracked)| InternalIndexEntry {
                 pane_keys: pane_keys.clone(),
-                internal_index,
+                internal_index: tracked.internal_index,
+                verified: tracked.verified,
+                tab_id: tracked.tab_id,
+                position: tracked.position,
             })
             .collect()
     }
 }
+
+/// Tabs whose order relative to the other surviving tabs changed. Tabs that
+/// only shifted because a neighbour was opened or closed are not moves, and of
+/// a swapped pair only the tabs outside the longest preserved run are
+/// reported.
+fn moved_tabs(previous: &HashMap<usize, usize>, current: &HashMap<usize, usize>) -> Vec<usize> {
+    let mut surviving: Vec<usize> = previous
+        .keys()
+        .filter(|tab_id| current.contains_key(tab_id))
+        .copied()
+        .collect();
+    surviving.sort_by_key(|tab_id| previous[tab_id]);
+
+    let new_ranks: Vec<usize> = surviving.iter().map(|tab_id| current[tab_id]).collect();
+    let kept = longest_increasing_run(&new_ranks);
+
+    surviving
+        .into_iter()
+        .enumerate()
+        .filter(|(index, _)| !kept.contains(index))
+        .map(|(_, tab_id)| tab_id)
+        .collect()
+}
+
+fn longest_increasing_run(values: &[usize]) -> HashSet<usize> {
+    let mut lengths = vec![1usize; values.len()];
+    let mut previous = vec![None; values.len()];
+    for i in 0..values.len() {
+        for j in 0..i {
+            if values[j] < values[i] && lengths[j] + 1 > lengths[i] {
+                lengths[i] = lengths[j] + 1;
+                previous[i] = Some(j);
+            }
+        }
+    }
+
+    let mut kept = HashSet::new();
+    let mut cursor = (0..values.len()).max_by_key(|&i| (lengths[i], std::cmp::Reverse(i)));
+    while let Some(i) = cursor {
+        kept.insert(i);
+        cursor = previous[i];
+    }
+    kept
+}